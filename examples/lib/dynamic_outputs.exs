@@ -9,11 +9,10 @@ defmodule DynamicOutputsPipeline do
   require Membrane.Logger
 
   alias Membrane.LiveCompositor
-  alias Membrane.LiveCompositor.{Context, Encoder, OutputOptions, Request}
+  alias Membrane.LiveCompositor.{Context, Encoder, Layouts, OutputOptions, Request, Scene}
 
   @output_width 1920
   @output_height 1080
-  @layout_id "layout"
 
   @impl true
   def handle_init(_ctx, %{sample_path: sample_path, server_setup: server_setup}) do
@@ -97,22 +96,16 @@ defmodule DynamicOutputsPipeline do
 
   @impl true
   def handle_child_notification(
-        {:request_result, request,
-         {:error, %Req.Response{status: response_code, body: response_body}}},
+        {:request_result, request, {:error, error}},
         :live_compositor,
         _membrane_ctx,
-        state
+        _state
       ) do
-    if response_code != 200 do
-      raise """
-      LiveCompositor request failed:
-      Request: `#{inspect(request)}.
-      Response code: #{response_code}.
-      Response body: #{inspect(response_body)}.
-      """
-    end
-
-    {[], state}
+    raise """
+    LiveCompositor request failed:
+    Request: #{inspect(request)}.
+    Error: #{inspect(error)}.
+    """
   end
 
   @impl true
@@ -129,7 +122,6 @@ defmodule DynamicOutputsPipeline do
     spec =
       child({:video_src, input_id}, %Membrane.File.Source{location: state.sample_path})
       |> child({:input_parser, input_id}, %Membrane.H264.Parser{
-        output_alignment: :nalu,
         generate_best_effort_timestamps: %{framerate: {30, 1}}
       })
       |> child({:realtimer, input_id}, Membrane.Realtimer)
@@ -169,21 +161,13 @@ defmodule DynamicOutputsPipeline do
     {[remove_children: output_group_id(output_id)], state}
   end
 
-  @spec scene(LiveCompositor.Context.t(), LiveCompositor.output_id()) :: map()
+  @spec scene(LiveCompositor.Context.t(), LiveCompositor.output_id()) :: Scene.component()
   defp scene(ctx, output_id) do
-    %{
-      id: "tile_#{output_id}",
-      type: :tiles,
-      padding: 10,
-      transition: %{
-        duration_ms: 300
-      },
-      children:
-        ctx.video_inputs
-        |> Enum.map(fn input_id ->
-          %{type: :input_stream, input_id: input_id, id: "#{output_id}_#{input_id}"}
-        end)
-    }
+    Layouts.grid(ctx.video_inputs, %{width: @output_width, height: @output_height},
+      id_prefix: output_id,
+      margin: 10,
+      transition: %{duration_ms: 300}
+    )
   end
 
   defp output_group_id(output_id) do
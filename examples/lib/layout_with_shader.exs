@@ -6,7 +6,7 @@ defmodule LayoutWithShaderPipeline do
   require Membrane.Logger
 
   alias Membrane.LiveCompositor
-  alias Membrane.LiveCompositor.{Encoder, Request}
+  alias Membrane.LiveCompositor.{Encoder, Request, Scene}
   alias Membrane.PortAudio
 
   @output_width 1920
@@ -25,7 +25,6 @@ defmodule LayoutWithShaderPipeline do
     spec = [
       child({:video_src, 0}, %Membrane.File.Source{location: video_sample_path})
       |> child({:input_parser, 0}, %Membrane.H264.Parser{
-        output_alignment: :nalu,
         generate_best_effort_timestamps: %{framerate: {30, 1}}
       })
       |> child({:realtimer, 0}, Membrane.Realtimer)
@@ -117,22 +116,16 @@ defmodule LayoutWithShaderPipeline do
 
   @impl true
   def handle_child_notification(
-        {:request_result, request,
-         {:error, %Req.Response{status: response_code, body: response_body}}},
+        {:request_result, request, {:error, error}},
         :live_compositor,
         _membrane_ctx,
-        state
+        _state
       ) do
-    if response_code != 200 do
-      raise """
-      LiveCompositor request failed:
-      Request: `#{inspect(request)}.
-      Response code: #{response_code}.
-      Response body: #{inspect(response_body)}.
-      """
-    end
-
-    {[], state}
+    raise """
+    LiveCompositor request failed:
+    Request: #{inspect(request)}.
+    Error: #{inspect(error)}.
+    """
   end
 
   @impl true
@@ -172,7 +165,6 @@ defmodule LayoutWithShaderPipeline do
       spec = [
         child({:video_src, videos_count}, %Membrane.File.Source{location: state.video_sample_path})
         |> child({:input_parser, videos_count}, %Membrane.H264.Parser{
-          output_alignment: :nalu,
           generate_best_effort_timestamps: %{framerate: {30, 1}}
         })
         |> child({:realtimer, videos_count}, Membrane.Realtimer)
@@ -188,19 +180,17 @@ defmodule LayoutWithShaderPipeline do
     end
   end
 
-  @spec scene(list(LiveCompositor.input_id())) :: map()
+  @spec scene(list(LiveCompositor.input_id())) :: Scene.component()
   defp scene(input_ids) do
-    %{
-      type: :shader,
+    %Scene.Shader{
       shader_id: @shader_id,
       resolution: %{
         width: @output_width,
         height: @output_height
       },
       children: [
-        %{
+        %Scene.Tiles{
           id: "tile_0",
-          type: :tiles,
           width: @output_width,
           height: @output_height,
           background_color_rgba: "#000088FF",
@@ -209,7 +199,7 @@ defmodule LayoutWithShaderPipeline do
           },
           margin: 10,
           children:
-            input_ids |> Enum.map(fn input_id -> %{type: :input_stream, input_id: input_id} end)
+            input_ids |> Enum.map(fn input_id -> %Scene.InputStream{input_id: input_id} end)
         }
       ]
     }
@@ -6,7 +6,7 @@ defmodule OfflineProcessing do
   require Membrane.Logger
 
   alias Membrane.LiveCompositor
-  alias Membrane.LiveCompositor.Request
+  alias Membrane.LiveCompositor.{Request, Scene}
 
   @video_output_id "video_output_1"
   @audio_output_id "audio_output_1"
@@ -15,18 +15,25 @@ defmodule OfflineProcessing do
   @output_height 720
   @shader_path "./lib/example_shader.wgsl"
   @output_file "samples/offline_processing_output.mp4"
+  @input_id "input_0"
 
   @impl true
   def handle_init(_ctx, %{server_setup: server_setup, sample_path: sample_path}) do
     spec = [
-      child(:file_source, %Membrane.File.Source{location: sample_path})
-      |> child(:mp4_demuxer, Membrane.MP4.Demuxer.ISOM),
       child(:live_compositor, %LiveCompositor{
         framerate: {30, 1},
         server_setup: server_setup,
         composing_strategy: :offline_processing,
         init_requests: [
-          register_shader_request_body()
+          register_shader_request_body(),
+          # MP4 file is read by the LiveCompositor server, so no demuxing or decoding
+          # is needed in the pipeline.
+          %Request.RegisterMp4Input{
+            input_id: @input_id,
+            path: Path.expand(sample_path),
+            offset: Membrane.Time.seconds(5),
+            required: true
+          }
         ]
       })
       |> via_out(Pad.ref(:video_output, @video_output_id),
@@ -40,7 +47,7 @@ defmodule OfflineProcessing do
           initial: %{
             root:
               scene([
-                %{type: :input_stream, input_id: "video_input_0", id: "child_0"}
+                %Scene.InputStream{input_id: @input_id, id: "child_0"}
               ])
           }
         ]
@@ -60,7 +67,7 @@ defmodule OfflineProcessing do
           send_eos_when: :all_inputs,
           initial: %{
             inputs: [
-              %{input_id: "audio_input_0", volume: 0.2}
+              %{input_id: @input_id, volume: 0.2}
             ]
           }
         ]
@@ -72,113 +79,6 @@ defmodule OfflineProcessing do
     {[spec: spec], %{registered_compositor_streams: 0}}
   end
 
-  @impl true
-  def handle_setup(_ctx, state) do
-    schedule_scene_update_1 =
-      %Request.UpdateVideoOutput{
-        output_id: @video_output_id,
-        root:
-          scene([
-            %{type: :input_stream, input_id: "video_input_0", id: "child_0"},
-            %{type: :input_stream, input_id: "video_input_0", id: "child_2"}
-          ]),
-        schedule_time: Membrane.Time.seconds(10)
-      }
-
-    schedule_scene_update_2 = %Request.UpdateVideoOutput{
-      output_id: @video_output_id,
-      root:
-        scene([
-          %{type: :input_stream, input_id: "video_input_0", id: "child_0"},
-          %{type: :input_stream, input_id: "video_input_0", id: "child_1"},
-          %{type: :input_stream, input_id: "video_input_0", id: "child_2"}
-        ]),
-      schedule_time: Membrane.Time.seconds(20)
-    }
-
-    schedule_audio_update = %Request.UpdateAudioOutput{
-      output_id: @audio_output_id,
-      inputs: [
-        %{input_id: "audio_input_0"}
-      ],
-      schedule_time: Membrane.Time.seconds(30)
-    }
-
-    {[
-       notify_child: {:live_compositor, schedule_scene_update_1},
-       notify_child: {:live_compositor, schedule_scene_update_2},
-       notify_child: {:live_compositor, schedule_audio_update}
-     ], state}
-  end
-
-  @impl true
-  def handle_child_notification(
-        {:new_tracks, tracks},
-        :mp4_demuxer,
-        _membrane_ctx,
-        state
-      ) do
-    spec =
-      tracks
-      |> Enum.map(fn {track_id, stream_format} ->
-        case stream_format do
-          %Membrane.H264{} ->
-            get_child(:mp4_demuxer)
-            |> via_out(Pad.ref(:output, track_id))
-            |> child(:mp4_input_parser, %Membrane.H264.Parser{
-              output_alignment: :nalu,
-              output_stream_structure: :annexb,
-              generate_best_effort_timestamps: %{framerate: {30, 1}}
-            })
-            |> via_in(Pad.ref(:video_input, "video_input_0"),
-              options: [
-                offset: Membrane.Time.seconds(5),
-                required: true
-              ]
-            )
-            |> get_child(:live_compositor)
-
-          %Membrane.AAC{sample_rate: sample_rate, channels: channels} ->
-            get_child(:mp4_demuxer)
-            |> via_out(Pad.ref(:output, track_id))
-            |> child(:aac_parser, Membrane.AAC.Parser)
-            |> child(:aac_decoder, Membrane.AAC.FDK.Decoder)
-            |> child(:converter, %Membrane.FFmpeg.SWResample.Converter{
-              input_stream_format: %Membrane.RawAudio{
-                channels: channels,
-                sample_format: :s16le,
-                sample_rate: sample_rate
-              },
-              output_stream_format: %Membrane.RawAudio{
-                channels: 2,
-                sample_format: :s16le,
-                sample_rate: 48_000
-              }
-            })
-            |> child(:audio_encoder, %Membrane.Opus.Encoder{
-              application: :audio,
-              input_stream_format: %Membrane.RawAudio{
-                channels: 2,
-                sample_format: :s16le,
-                sample_rate: 48_000
-              }
-            })
-            |> child(:audio_parser, %Membrane.Opus.Parser{
-              generate_best_effort_timestamps?: true
-            })
-            |> via_in(Pad.ref(:audio_input, "audio_input_0"),
-              options: [
-                offset: Membrane.Time.seconds(5),
-                required: true
-              ]
-            )
-            |> get_child(:live_compositor)
-        end
-      end)
-
-    {[spec: spec], state}
-  end
-
   @impl true
   def handle_child_notification(
         {:input_delivered, Pad.ref(pad_type, pad_id), ctx},
@@ -189,8 +89,9 @@ defmodule OfflineProcessing do
     state = %{state | registered_compositor_streams: state.registered_compositor_streams + 1}
 
     if state.registered_compositor_streams == 4 do
-      # send start when all inputs are connected
-      {[notify_child: {:live_compositor, :start_composing}], state}
+      # schedule updates and send start when all inputs are connected, updates
+      # can only reference inputs that are already connected
+      {scheduled_updates() ++ [notify_child: {:live_compositor, :start_composing}], state}
     else
       {[], state}
     end
@@ -206,8 +107,9 @@ defmodule OfflineProcessing do
     state = %{state | registered_compositor_streams: state.registered_compositor_streams + 1}
 
     if state.registered_compositor_streams == 4 do
-      # send start when all inputs are connected
-      {[notify_child: {:live_compositor, :start_composing}], state}
+      # schedule updates and send start when all inputs are connected, updates
+      # can only reference inputs that are already connected
+      {scheduled_updates() ++ [notify_child: {:live_compositor, :start_composing}], state}
     else
       {[], state}
     end
@@ -229,22 +131,16 @@ defmodule OfflineProcessing do
 
   @impl true
   def handle_child_notification(
-        {:request_result, request,
-         {:error, %Req.Response{status: response_code, body: response_body}}},
+        {:request_result, request, {:error, error}},
         :live_compositor,
         _membrane_ctx,
-        state
+        _state
       ) do
-    if response_code != 200 do
-      raise """
-      LiveCompositor request failed:
-      Request: #{inspect(request)}.
-      Response code: #{response_code}.
-      Response body: #{inspect(response_body)}.
-      """
-    end
-
-    {[], state}
+    raise """
+    LiveCompositor request failed:
+    Request: #{inspect(request)}.
+    Error: #{inspect(error)}.
+    """
   end
 
   @impl true
@@ -266,19 +162,55 @@ defmodule OfflineProcessing do
     {[], state}
   end
 
-  @spec scene(any()) :: map()
+  defp scheduled_updates() do
+    schedule_scene_update_1 =
+      %Request.UpdateVideoOutput{
+        output_id: @video_output_id,
+        root:
+          scene([
+            %Scene.InputStream{input_id: @input_id, id: "child_0"},
+            %Scene.InputStream{input_id: @input_id, id: "child_2"}
+          ]),
+        schedule_time: Membrane.Time.seconds(10)
+      }
+
+    schedule_scene_update_2 = %Request.UpdateVideoOutput{
+      output_id: @video_output_id,
+      root:
+        scene([
+          %Scene.InputStream{input_id: @input_id, id: "child_0"},
+          %Scene.InputStream{input_id: @input_id, id: "child_1"},
+          %Scene.InputStream{input_id: @input_id, id: "child_2"}
+        ]),
+      schedule_time: Membrane.Time.seconds(20)
+    }
+
+    schedule_audio_update = %Request.UpdateAudioOutput{
+      output_id: @audio_output_id,
+      inputs: [
+        %{input_id: @input_id}
+      ],
+      schedule_time: Membrane.Time.seconds(30)
+    }
+
+    [
+      notify_child: {:live_compositor, schedule_scene_update_1},
+      notify_child: {:live_compositor, schedule_scene_update_2},
+      notify_child: {:live_compositor, schedule_audio_update}
+    ]
+  end
+
+  @spec scene(list(Scene.component())) :: Scene.component()
   defp scene(children) do
-    %{
-      type: :shader,
+    %Scene.Shader{
       shader_id: @shader_id,
       resolution: %{
         width: @output_width,
         height: @output_height
       },
       children: [
-        %{
+        %Scene.Tiles{
           id: "tiles_0",
-          type: :tiles,
           width: @output_width,
           height: @output_height,
           background_color_rgba: "#000088FF",
@@ -6,12 +6,13 @@ defmodule TransitionPipeline do
   require Membrane.Logger
 
   alias Membrane.LiveCompositor
-  alias Membrane.LiveCompositor.{Context, Encoder, OutputOptions, Request}
+  alias Membrane.LiveCompositor.{Context, Encoder, Layouts, OutputOptions, Request, Scene}
 
   @output_width 1280
   @output_height 720
   @output_id "output"
-  @rescaler_id "rescaler"
+  @resolution %{width: @output_width, height: @output_height}
+  @transition %{duration_ms: 1000}
 
   @impl true
   def handle_init(_ctx, %{sample_path: sample_path, server_setup: server_setup}) do
@@ -27,7 +28,7 @@ defmodule TransitionPipeline do
           },
           width: @output_width,
           height: @output_height,
-          initial: %{root: %{type: :view}}
+          initial: %{root: %Scene.View{}}
         ]
       )
       |> child(:output_parser, Membrane.H264.Parser)
@@ -57,13 +58,19 @@ defmodule TransitionPipeline do
         "input_0" ->
           %Request.UpdateVideoOutput{
             output_id: @output_id,
-            root: single_input_scene("input_0")
+            root: Layouts.picture_in_picture(["input_0"], @resolution, transition: @transition)
           }
 
         "input_1" ->
           %Request.UpdateVideoOutput{
             output_id: @output_id,
-            root: double_inputs_scene("input_0", "input_1")
+            root:
+              Layouts.picture_in_picture(["input_1", "input_0"], @resolution,
+                corner: :top_right,
+                margin: 10,
+                scale: 1 / 3,
+                transition: @transition
+              )
           }
       end
 
@@ -88,22 +95,16 @@ defmodule TransitionPipeline do
 
   @impl true
   def handle_child_notification(
-        {:request_result, request,
-         {:error, %Req.Response{status: response_code, body: response_body}}},
+        {:request_result, request, {:error, error}},
         :live_compositor,
         _membrane_ctx,
-        state
+        _state
       ) do
-    if response_code != 200 do
-      raise """
-      LiveCompositor request failed:
-      Request: `#{inspect(request)}.
-      Response code: #{response_code}.
-      Response body: #{inspect(response_body)}.
-      """
-    end
-
-    {[], state}
+    raise """
+    LiveCompositor request failed:
+    Request: #{inspect(request)}.
+    Error: #{inspect(error)}.
+    """
   end
 
   @impl true
@@ -122,7 +123,6 @@ defmodule TransitionPipeline do
         location: sample_path
       })
       |> child({:input_parser, input_id}, %Membrane.H264.Parser{
-        output_alignment: :nalu,
         generate_best_effort_timestamps: %{framerate: {30, 1}}
       })
       |> child({:realtimer, input_id}, Membrane.Realtimer)
@@ -131,62 +131,6 @@ defmodule TransitionPipeline do
 
     {[spec: spec], state}
   end
-
-  defp single_input_scene(input_id) do
-    %{
-      type: :view,
-      children: [
-        %{
-          type: :rescaler,
-          mode: :fit,
-          id: @rescaler_id,
-          top: 0,
-          right: 0,
-          width: @output_width,
-          height: @output_height,
-          transition: %{
-            duration_ms: 1000
-          },
-          child: %{
-            type: :input_stream,
-            input_id: input_id
-          }
-        }
-      ]
-    }
-  end
-
-  defp double_inputs_scene(first_input_id, second_input_id) do
-    %{
-      type: :view,
-      children: [
-        %{
-          type: :rescaler,
-          mode: :fit,
-          child: %{
-            type: :input_stream,
-            input_id: second_input_id
-          }
-        },
-        %{
-          type: :rescaler,
-          mode: :fit,
-          id: @rescaler_id,
-          top: 10,
-          right: 10,
-          width: div(@output_width, 3),
-          height: div(@output_height, 3),
-          transition: %{
-            duration_ms: 1000
-          },
-          child: %{
-            type: :input_stream,
-            input_id: first_input_id
-          }
-        }
-      ]
-    }
-  end
 end
 
 Utils.FFmpeg.generate_sample_video()
@@ -3,13 +3,15 @@ defmodule Membrane.LiveCompositor.ApiClient do
 
   require Membrane.Logger
 
+  alias Membrane.LiveCompositor.{Error, RequestPolicy}
+
+  @pool __MODULE__.Pool
+  @pool_size 10
+
   @type http_method :: :post | :get
   @type request :: {http_method(), path :: String.t(), body :: any()}
 
-  @type request_result ::
-          {:ok, Req.Response.t()}
-          | {:error, {:response, Req.Response.t()}}
-          | {:error, Exception.t()}
+  @type request_result :: {:ok, body :: any()} | {:error, Error.t()}
 
   defprotocol IntoRequest do
     @moduledoc false
@@ -20,26 +22,59 @@ defmodule Membrane.LiveCompositor.ApiClient do
     def into_request(data)
   end
 
-  @spec start_composing({:inet.ip_address(), :inet.port_number()}) :: request_result()
-  def start_composing(lc_address) do
-    {:post, "/api/start", %{}} |> send_request(lc_address)
+  @spec start_composing({:inet.ip_address(), :inet.port_number()}, [send_opt()]) ::
+          request_result()
+  def start_composing(lc_address, opts \\ []) do
+    {:post, "/api/start", %{}} |> send_request(lc_address, opts)
   end
 
-  @spec get_status({:inet.ip_address(), :inet.port_number()}) :: request_result()
-  def get_status(lc_address) do
-    {:get, "/status", nil} |> send_request(lc_address)
+  @spec get_status({:inet.ip_address(), :inet.port_number()}, [send_opt()]) :: request_result()
+  def get_status(lc_address, opts \\ []) do
+    {:get, "/status", nil} |> send_request(lc_address, opts)
   end
 
-  @spec send_request(request(), {:inet.ip_address(), :inet.port_number()}) :: request_result()
-  def send_request(request, lc_address) do
-    {lc_ip, lc_port} = lc_address
-    lc_ip = lc_ip |> Tuple.to_list() |> Enum.join(".")
+  @typedoc """
+  - `:policy` - Timeouts and retries of the request. Defaults to `%RequestPolicy{}`.
+  - `:retry?` - Whether the request can be retried. Defaults to `false`.
+  - `:base_url` - URL of the server built with `base_url/1`. If not defined, it's built from
+  the address of the server.
+  """
+  @type send_opt ::
+          {:policy, RequestPolicy.t()} | {:retry?, boolean()} | {:base_url, String.t()}
 
+  # Persistent connections to LiveCompositor servers are kept in a single pool started with
+  # the application, so bins don't create a pool (and its name) each. Finch keeps a separate
+  # set of connections for every server.
+  @spec pool_child_spec() :: Supervisor.child_spec()
+  def pool_child_spec() do
+    Supervisor.child_spec({Finch, name: @pool, pools: %{default: [size: @pool_size]}}, [])
+  end
+
+  # Duration of every request is emitted as a telemetry event, see "Telemetry" section
+  # in the docs of `Membrane.LiveCompositor`.
+  @spec send_request(request(), {:inet.ip_address(), :inet.port_number()}, [send_opt()]) ::
+          request_result()
+  def send_request(request, lc_address, opts \\ []) do
     {method, route, body} = request
-    {:ok, _apps} = Application.ensure_all_started(:req)
+    policy = Keyword.get(opts, :policy, %RequestPolicy{})
+    timeout_ms = Membrane.Time.as_milliseconds(policy.timeout, :round)
+
+    # Req counts retries from 0
+    retry_delay_ms = fn retry_count ->
+      RequestPolicy.retry_delay(policy, retry_count + 1) |> Membrane.Time.as_milliseconds(:round)
+    end
 
-    retry_delay_ms = fn retry_count -> retry_count * 100 end
-    req = Req.new(base_url: "http://#{lc_ip}:#{lc_port}", retry_delay: retry_delay_ms)
+    req =
+      Req.new(
+        base_url: Keyword.get_lazy(opts, :base_url, fn -> base_url(lc_address) end),
+        finch: @pool,
+        receive_timeout: timeout_ms,
+        retry: if(Keyword.get(opts, :retry?, false), do: :transient, else: false),
+        max_retries: policy.max_retries,
+        retry_delay: retry_delay_ms
+      )
+
+    start_time = System.monotonic_time()
 
     response =
       case method do
@@ -47,16 +82,40 @@ defmodule Membrane.LiveCompositor.ApiClient do
         :get -> Req.get(req, url: route)
       end
 
-    handle_request_result(response)
+    result = handle_request_result(response)
+
+    :telemetry.execute(
+      [:membrane_live_compositor, :api_request, :stop],
+      %{duration: System.monotonic_time() - start_time},
+      %{method: method, route: route, lc_address: lc_address, result: elem(result, 0)}
+    )
+
+    result
   end
 
   @spec handle_request_result({:ok, Req.Response.t()} | {:error, Exception.t()}) ::
           request_result()
   defp handle_request_result(req_result) do
     case req_result do
-      {:ok, resp = %Req.Response{status: 200}} -> {:ok, resp}
-      {:ok, resp} -> {:error, {:response, resp}}
-      {:error, exception} -> {:error, exception}
+      {:ok, %Req.Response{status: 200, body: body}} -> {:ok, body}
+      {:ok, resp} -> {:error, Error.from_response(resp)}
+      {:error, exception} -> {:error, Error.from_exception(exception)}
     end
   end
+
+  @spec local_ip_towards({:inet.ip_address(), :inet.port_number()}) :: :inet.ip_address()
+  def local_ip_towards({lc_ip, lc_port}) do
+    # Connect to the compositor to find out which local interface can be
+    # reached from it.
+    {:ok, socket} = :gen_tcp.connect(lc_ip, lc_port, [:binary, active: false])
+    {:ok, {local_ip, _port}} = :inet.sockname(socket)
+    :gen_tcp.close(socket)
+    local_ip
+  end
+
+  @spec base_url({:inet.ip_address(), :inet.port_number()}) :: String.t()
+  def base_url({lc_ip, lc_port}) do
+    lc_ip = lc_ip |> Tuple.to_list() |> Enum.join(".")
+    "http://#{lc_ip}:#{lc_port}"
+  end
 end
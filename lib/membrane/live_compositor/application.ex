@@ -0,0 +1,13 @@
+defmodule Membrane.LiveCompositor.Application do
+  @moduledoc false
+
+  use Application
+
+  alias Membrane.LiveCompositor.ApiClient
+
+  @impl true
+  def start(_type, _args) do
+    children = [ApiClient.pool_child_spec()]
+    Supervisor.start_link(children, strategy: :one_for_one, name: __MODULE__.Supervisor)
+  end
+end
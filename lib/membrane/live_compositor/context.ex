@@ -8,18 +8,41 @@ defmodule Membrane.LiveCompositor.Context do
   alias Membrane.LiveCompositor
   alias Membrane.Pad
 
-  defstruct video_inputs: [], audio_inputs: [], video_outputs: [], audio_outputs: []
+  defstruct video_inputs: [],
+            audio_inputs: [],
+            video_outputs: [],
+            audio_outputs: [],
+            mp4_inputs: [],
+            shaders: [],
+            images: [],
+            web_renderers: []
 
   @typedoc """
-  Context of LiveCompositor. Specifies LiveCompositor inputs and outputs.
+  Context of LiveCompositor. Specifies LiveCompositor inputs and outputs, and renderers
+  (shaders, images and web renderers) registered by this bin.
+
+  `mp4_inputs` are inputs registered with `Membrane.LiveCompositor.Request.RegisterMp4Input`.
+  They are not linked to any pad, so they are not included in `video_inputs` and `audio_inputs`.
   """
   @type t :: %__MODULE__{
           video_inputs: list(LiveCompositor.input_id()),
           audio_inputs: list(LiveCompositor.input_id()),
           video_outputs: list(LiveCompositor.output_id()),
-          audio_outputs: list(LiveCompositor.output_id())
+          audio_outputs: list(LiveCompositor.output_id()),
+          mp4_inputs: list(LiveCompositor.input_id()),
+          shaders: list(String.t()),
+          images: list(String.t()),
+          web_renderers: list(String.t())
         }
 
+  @typedoc """
+  Renderer registered by the LiveCompositor bin.
+  """
+  @type renderer ::
+          {:shader, shader_id :: String.t()}
+          | {:image, image_id :: String.t()}
+          | {:web_renderer, instance_id :: String.t()}
+
   @doc false
   @spec add_stream(Pad.ref(), t()) :: t()
   def add_stream(Pad.ref(pad_type, id), ctx = %__MODULE__{}) do
@@ -55,14 +78,26 @@ defmodule Membrane.LiveCompositor.Context do
     end
   end
 
+  @doc false
+  @spec add_mp4_input(LiveCompositor.input_id(), t()) :: t()
+  def add_mp4_input(input_id, ctx = %__MODULE__{mp4_inputs: mp4_inputs}) do
+    ensure_no_inputs_with_id(input_id, ctx)
+    %__MODULE__{ctx | mp4_inputs: mp4_inputs ++ [input_id]}
+  end
+
   @doc false
   @spec remove_input(LiveCompositor.input_id(), t()) :: t()
   def remove_input(input_id, ctx = %__MODULE__{audio_inputs: audio, video_inputs: video}) do
     audio = audio |> Enum.reject(fn id -> input_id == id end)
     video = video |> Enum.reject(fn id -> input_id == id end)
-    %__MODULE__{ctx | audio_inputs: audio, video_inputs: video}
+    mp4 = ctx.mp4_inputs |> Enum.reject(fn id -> input_id == id end)
+    %__MODULE__{ctx | audio_inputs: audio, video_inputs: video, mp4_inputs: mp4}
   end
 
+  @doc false
+  @spec input_registered?(LiveCompositor.input_id(), t()) :: boolean()
+  def input_registered?(input_id, ctx), do: get_input(input_id, ctx) != nil
+
   @doc false
   @spec remove_output(LiveCompositor.output_id(), t()) :: t()
   def remove_output(output_id, ctx = %__MODULE__{audio_outputs: audio, video_outputs: video}) do
@@ -71,9 +106,44 @@ defmodule Membrane.LiveCompositor.Context do
     %__MODULE__{ctx | audio_outputs: audio, video_outputs: video}
   end
 
+  @doc false
+  @spec add_renderer(renderer(), t()) :: t()
+  def add_renderer({:shader, shader_id}, ctx = %__MODULE__{shaders: shaders}) do
+    %__MODULE__{ctx | shaders: shaders ++ [shader_id]}
+  end
+
+  def add_renderer({:image, image_id}, ctx = %__MODULE__{images: images}) do
+    %__MODULE__{ctx | images: images ++ [image_id]}
+  end
+
+  def add_renderer({:web_renderer, instance_id}, ctx = %__MODULE__{web_renderers: ids}) do
+    %__MODULE__{ctx | web_renderers: ids ++ [instance_id]}
+  end
+
+  @doc false
+  @spec remove_renderer(renderer(), t()) :: t()
+  def remove_renderer({:shader, shader_id}, ctx = %__MODULE__{shaders: shaders}) do
+    %__MODULE__{ctx | shaders: shaders |> Enum.reject(fn id -> shader_id == id end)}
+  end
+
+  def remove_renderer({:image, image_id}, ctx = %__MODULE__{images: images}) do
+    %__MODULE__{ctx | images: images |> Enum.reject(fn id -> image_id == id end)}
+  end
+
+  def remove_renderer({:web_renderer, instance_id}, ctx = %__MODULE__{web_renderers: ids}) do
+    %__MODULE__{ctx | web_renderers: ids |> Enum.reject(fn id -> instance_id == id end)}
+  end
+
+  @doc false
+  @spec renderer_registered?(renderer(), t()) :: boolean()
+  def renderer_registered?({:shader, shader_id}, ctx), do: shader_id in ctx.shaders
+  def renderer_registered?({:image, image_id}, ctx), do: image_id in ctx.images
+  def renderer_registered?({:web_renderer, id}, ctx), do: id in ctx.web_renderers
+
   defp get_input(input_id, ctx) do
     ctx.audio_inputs |> Enum.find(fn id -> input_id == id end) ||
-      ctx.video_inputs |> Enum.find(fn id -> input_id == id end)
+      ctx.video_inputs |> Enum.find(fn id -> input_id == id end) ||
+      ctx.mp4_inputs |> Enum.find(fn id -> input_id == id end)
   end
 
   defp ensure_no_inputs_with_id(input_id, ctx) do
@@ -81,8 +151,8 @@ defmodule Membrane.LiveCompositor.Context do
 
     if input != nil do
       raise """
-      You can only register one input at the time with a specific id. Input pad
-      with id "#{input_id}" was already connected.
+      You can only register one input at the time with a specific id. Input pad or MP4 input
+      with id "#{input_id}" was already registered.
       """
     end
   end
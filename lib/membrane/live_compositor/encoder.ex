@@ -45,6 +45,33 @@ defmodule Membrane.LiveCompositor.Encoder do
     end
   end
 
+  defmodule FFmpegVp8 do
+    @moduledoc """
+    Options for VP8 encoder from FFmpeg.
+    """
+
+    @typedoc """
+    Raw FFmpeg encoder options. See [docs](https://ffmpeg.org/ffmpeg-codecs.html#libvpx) for more.
+    """
+    @type ffmpeg_options :: %{(String.t() | atom()) => String.t()} | nil
+
+    defstruct ffmpeg_options: nil
+
+    @type t :: %__MODULE__{
+            ffmpeg_options: ffmpeg_options()
+          }
+  end
+
+  defimpl Jason.Encoder, for: FFmpegVp8 do
+    @spec encode(FFmpegVp8.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(
+        Map.take(value, [:ffmpeg_options]) |> Map.put(:type, :ffmpeg_vp8),
+        opts
+      )
+    end
+  end
+
   defmodule Opus do
     @moduledoc """
     Options for OPUS encoder.
@@ -64,6 +91,11 @@ defmodule Membrane.LiveCompositor.Encoder do
             preset: encoder_preset(),
             channels: channels()
           }
+
+    @doc false
+    @spec channels_count(t()) :: 1 | 2
+    def channels_count(%__MODULE__{channels: :mono}), do: 1
+    def channels_count(%__MODULE__{channels: :stereo}), do: 2
   end
 
   defimpl Jason.Encoder, for: Opus do
@@ -0,0 +1,114 @@
+defmodule Membrane.LiveCompositor.Error do
+  @moduledoc """
+  Error returned when a request to the LiveCompositor server fails.
+
+  There are two kinds of failures:
+  - The server responded with an error. In that case `:http_status` is set and `:error_code`,
+  `:message` and `:stack` are taken from the response body.
+  - The request didn't reach the server or the response was not received. In that case
+  `:error_code` is set to `:transport_error` and `:reason` contains the reason of the failure
+  e.g. `:econnrefused` or `:timeout`.
+
+  Request that the bin can't handle is rejected without sending it to the server, with
+  `:reason` set to `:unsupported_request`.
+  """
+
+  @server_error_codes [
+    "INPUT_STREAM_ALREADY_REGISTERED",
+    "INPUT_STREAM_NOT_FOUND",
+    "INPUT_STREAM_STILL_IN_USE",
+    "OUTPUT_STREAM_ALREADY_REGISTERED",
+    "OUTPUT_STREAM_NOT_FOUND",
+    "OUTPUT_NOT_REGISTERED",
+    "ENTITY_ALREADY_REGISTERED",
+    "ENTITY_NOT_FOUND",
+    "RENDERER_STILL_IN_USE",
+    "SHADER_COMPILATION_ERROR",
+    "IMAGE_RENDERER_CREATION_ERROR",
+    "WEB_RENDERER_INSTANCE_CREATION_ERROR",
+    "UNSUPPORTED_RESOLUTION",
+    "SCENE_BUILD_ERROR",
+    "UPDATE_SCENE_ERROR",
+    "PORT_ALREADY_IN_USE",
+    "COMPOSITOR_ALREADY_STARTED",
+    "MALFORMED_REQUEST",
+    "INTERNAL_SERVER_ERROR"
+  ]
+
+  @error_codes Map.new(@server_error_codes, fn code ->
+                 {code, code |> String.downcase() |> String.to_atom()}
+               end)
+
+  defstruct error_code: nil, message: nil, stack: [], http_status: nil, reason: nil
+
+  @typedoc """
+  Error code documented by the LiveCompositor server, converted to an atom, or `:transport_error`
+  if the server didn't respond. Error codes that are not known to this version of the plugin are
+  passed as strings.
+  """
+  @type error_code ::
+          :input_stream_already_registered
+          | :input_stream_not_found
+          | :input_stream_still_in_use
+          | :output_stream_already_registered
+          | :output_stream_not_found
+          | :output_not_registered
+          | :entity_already_registered
+          | :entity_not_found
+          | :renderer_still_in_use
+          | :shader_compilation_error
+          | :image_renderer_creation_error
+          | :web_renderer_instance_creation_error
+          | :unsupported_resolution
+          | :scene_build_error
+          | :update_scene_error
+          | :port_already_in_use
+          | :compositor_already_started
+          | :malformed_request
+          | :internal_server_error
+          | :transport_error
+          | String.t()
+
+  @typedoc """
+  - `:error_code` - See `t:error_code/0`.
+  - `:message` - Human readable description of the error.
+  - `:stack` - List of errors that caused this error, from the most general to the most specific.
+  - `:http_status` - HTTP status of the response, `nil` for transport errors.
+  - `:reason` - Reason of the transport error or `:unsupported_request`, `nil` if the server
+  responded.
+  """
+  @type t :: %__MODULE__{
+          error_code: error_code() | nil,
+          message: String.t() | nil,
+          stack: [String.t()],
+          http_status: non_neg_integer() | nil,
+          reason: any()
+        }
+
+  @doc false
+  @spec from_response(Req.Response.t()) :: t()
+  def from_response(%Req.Response{status: status, body: body}) do
+    case body do
+      %{"error_code" => error_code} ->
+        %__MODULE__{
+          error_code: Map.get(@error_codes, error_code, error_code),
+          message: body["message"],
+          stack: body["stack"] || [],
+          http_status: status
+        }
+
+      body ->
+        %__MODULE__{message: inspect(body), http_status: status}
+    end
+  end
+
+  @doc false
+  @spec from_exception(Exception.t()) :: t()
+  def from_exception(exception) do
+    %__MODULE__{
+      error_code: :transport_error,
+      message: Exception.message(exception),
+      reason: Map.get(exception, :reason, exception)
+    }
+  end
+end
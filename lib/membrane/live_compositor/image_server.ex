@@ -0,0 +1,113 @@
+defmodule Membrane.LiveCompositor.ImageServer do
+  @moduledoc false
+  # Minimal HTTP server that makes in-memory images available to the LiveCompositor server.
+  #
+  # Images registered with `data` are stored here and served under a random URL,
+  # that is passed to the LiveCompositor server instead of the image bytes. Server
+  # listens on the local interface that is used to reach the LiveCompositor server,
+  # so it also works when the compositor runs on a different host.
+
+  use GenServer
+
+  require Membrane.Logger
+
+  alias Membrane.LiveCompositor.ApiClient
+
+  @recv_timeout 5_000
+
+  @spec start_link({:inet.ip_address(), :inet.port_number()}) :: GenServer.on_start()
+  def start_link(lc_address) do
+    GenServer.start_link(__MODULE__, lc_address)
+  end
+
+  @spec put(pid(), String.t(), binary()) :: url :: String.t()
+  def put(server, image_id, data) do
+    GenServer.call(server, {:put, image_id, data})
+  end
+
+  @spec delete(pid(), String.t()) :: :ok
+  def delete(server, image_id) do
+    GenServer.call(server, {:delete, image_id})
+  end
+
+  @impl true
+  def init(lc_address) do
+    local_ip = ApiClient.local_ip_towards(lc_address)
+
+    {:ok, listen_socket} =
+      :gen_tcp.listen(0, [:binary, ip: local_ip, packet: :http_bin, active: false])
+
+    {:ok, port} = :inet.port(listen_socket)
+    server = self()
+    acceptor = spawn_link(fn -> accept_loop(listen_socket, server) end)
+
+    {:ok,
+     %{
+       listen_socket: listen_socket,
+       acceptor: acceptor,
+       base_url: "http://#{:inet.ntoa(local_ip)}:#{port}",
+       images: %{}
+     }}
+  end
+
+  @impl true
+  def handle_call({:put, image_id, data}, _from, state) do
+    token = :crypto.strong_rand_bytes(16) |> Base.url_encode64(padding: false)
+    images = Map.put(state.images, image_id, {token, data})
+    {:reply, "#{state.base_url}/#{token}", %{state | images: images}}
+  end
+
+  @impl true
+  def handle_call({:delete, image_id}, _from, state) do
+    {:reply, :ok, %{state | images: Map.delete(state.images, image_id)}}
+  end
+
+  @impl true
+  def handle_call({:get, token}, _from, state) do
+    image =
+      Enum.find_value(state.images, fn
+        {_image_id, {^token, data}} -> data
+        _other -> nil
+      end)
+
+    {:reply, image, state}
+  end
+
+  defp accept_loop(listen_socket, server) do
+    {:ok, socket} = :gen_tcp.accept(listen_socket)
+    pid = spawn(fn -> serve(socket, server) end)
+    :ok = :gen_tcp.controlling_process(socket, pid)
+    accept_loop(listen_socket, server)
+  end
+
+  defp serve(socket, server) do
+    response =
+      with {:ok, {:http_request, :GET, {:abs_path, "/" <> token}, _version}} <-
+             :gen_tcp.recv(socket, 0, @recv_timeout),
+           :ok <- skip_headers(socket),
+           data when is_binary(data) <- GenServer.call(server, {:get, token}) do
+        [
+          "HTTP/1.1 200 OK\r\n",
+          "Content-Type: application/octet-stream\r\n",
+          "Content-Length: #{byte_size(data)}\r\n",
+          "Connection: close\r\n\r\n",
+          data
+        ]
+      else
+        other ->
+          Membrane.Logger.debug("Image server failed to serve a request: #{inspect(other)}")
+          "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+      end
+
+    :gen_tcp.send(socket, response)
+    :gen_tcp.close(socket)
+  end
+
+  defp skip_headers(socket) do
+    case :gen_tcp.recv(socket, 0, @recv_timeout) do
+      {:ok, :http_eoh} -> :ok
+      {:ok, {:http_header, _index, _field, _reserved, _value}} -> skip_headers(socket)
+      other -> other
+    end
+  end
+end
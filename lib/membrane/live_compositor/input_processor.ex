@@ -0,0 +1,54 @@
+defmodule Membrane.LiveCompositor.VideoInputProcessor do
+  @moduledoc false
+  # Forwards encoded video of the input to the RTP payloader. The stream format of the input is
+  # not known when the pad is linked, so raw video of inputs linked without `raw_video_encoder`
+  # is rejected here with an error pointing to the missing option, instead of failing in
+  # the payloader.
+  #
+  # On `:keyframe_request` notification from the parent, `Membrane.KeyframeRequestEvent` is sent
+  # upstream, e.g. when the input is registered on a restarted server that can't decode the
+  # stream until the next keyframe.
+
+  use Membrane.Filter
+
+  def_options input_id: [
+                spec: String.t()
+              ]
+
+  def_input_pad :input,
+    accepted_format: any_of(Membrane.H264, Membrane.VP8, Membrane.VP9, Membrane.RawVideo),
+    flow_control: :auto
+
+  def_output_pad :output,
+    accepted_format: any_of(Membrane.H264, Membrane.VP8, Membrane.VP9),
+    flow_control: :auto
+
+  @impl true
+  def handle_init(_ctx, opt) do
+    {[], %{input_id: opt.input_id}}
+  end
+
+  @impl true
+  def handle_buffer(:input, buffer, _ctx, state) do
+    {[buffer: {:output, buffer}], state}
+  end
+
+  @impl true
+  def handle_parent_notification(:keyframe_request, _ctx, state) do
+    {[event: {:input, %Membrane.KeyframeRequestEvent{}}], state}
+  end
+
+  @impl true
+  def handle_stream_format(:input, %Membrane.RawVideo{}, _ctx, state) do
+    raise """
+    Video input #{inspect(state.input_id)} received #{inspect(Membrane.RawVideo)}, but \
+    `raw_video_encoder` option of the pad is not set. Set it to an encoder that produces \
+    the codec defined by `video_codec`, e.g. `%Membrane.H264.FFmpeg.Encoder{}`.
+    """
+  end
+
+  @impl true
+  def handle_stream_format(:input, stream_format, _ctx, state) do
+    {[stream_format: {:output, stream_format}], state}
+  end
+end
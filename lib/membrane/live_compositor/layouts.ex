@@ -0,0 +1,276 @@
+defmodule Membrane.LiveCompositor.Layouts do
+  @moduledoc """
+  Helpers that build scenes for common layouts.
+
+  Every layout is a `Membrane.LiveCompositor.Scene.View` with absolutely positioned
+  `Membrane.LiveCompositor.Scene.Rescaler` for each input. Id of a rescaler depends only
+  on the `:id_prefix` option and the input id, so when you switch between layouts (or add and
+  remove inputs) with the `:transition` option defined, the LiveCompositor will animate each
+  input from its old position to the new one. To make that possible, every position is
+  defined with `top` and `left`, because the LiveCompositor doesn't animate between positions
+  defined with different fields.
+
+  ```
+  root = Layouts.picture_in_picture(["input_0", "input_1"], %{width: 1280, height: 720},
+    corner: :top_right,
+    transition: %{duration_ms: 500}
+  )
+
+  %Request.UpdateVideoOutput{output_id: "output_0", root: root}
+  ```
+
+  All layouts accept following options:
+  - `:id_prefix` - Prefix of the component ids generated by the layout. Use different prefixes if
+  you want to use more than one layout in the same scene. Defaults to `"layout"`.
+  - `:transition` - Transition applied to every input when its position changes.
+  Defaults to `nil`.
+  - `:mode` - Rescaler mode used to fit inputs into their area. Defaults to `:fit`.
+  """
+
+  alias Membrane.LiveCompositor
+  alias Membrane.LiveCompositor.Scene
+
+  @typedoc """
+  Resolution of an area that layout should fill, usually the resolution of an output.
+  """
+  @type resolution :: %{width: non_neg_integer(), height: non_neg_integer()}
+
+  @type corner :: :top_left | :top_right | :bottom_left | :bottom_right
+
+  @type common_opt ::
+          {:id_prefix, String.t()}
+          | {:transition, Scene.transition() | nil}
+          | {:mode, :fit | :fill}
+
+  @typedoc """
+  - `:margin` - Space between tiles in pixels. Defaults to `0`.
+  """
+  @type grid_opt :: common_opt() | {:margin, non_neg_integer()}
+
+  @typedoc """
+  - `:corner` - Corner in which smaller inputs are placed. Defaults to `:bottom_right`.
+  - `:margin` - Distance between smaller inputs and the edges of the output in pixels.
+  Defaults to `20`.
+  - `:scale` - Size of smaller inputs relative to the output. Defaults to `0.25`.
+  """
+  @type picture_in_picture_opt ::
+          common_opt()
+          | {:corner, corner()}
+          | {:margin, non_neg_integer()}
+          | {:scale, float()}
+
+  @typedoc """
+  - `:filmstrip_height` - Height of the filmstrip relative to the output. Defaults to `0.2`.
+  - `:margin` - Space between items of the filmstrip in pixels. Defaults to `0`.
+  """
+  @type speaker_focus_opt ::
+          common_opt()
+          | {:filmstrip_height, float()}
+          | {:margin, non_neg_integer()}
+
+  @typedoc """
+  - `:margin` - Space between inputs in pixels. Defaults to `0`.
+  """
+  @type split_opt :: common_opt() | {:margin, non_neg_integer()}
+
+  @doc """
+  Arranges inputs on a grid. Number of columns and rows is chosen to make the grid as close to
+  a square as possible.
+  """
+  @spec grid([LiveCompositor.input_id()], resolution(), [grid_opt()]) :: Scene.View.t()
+  def grid(input_ids, resolution, opts \\ []) do
+    margin = Keyword.get(opts, :margin, 0)
+    count = length(input_ids)
+    columns = max(ceil(:math.sqrt(count)), 1)
+    rows = max(ceil(count / columns), 1)
+    tile_width = div(resolution.width - margin * (columns + 1), columns)
+    tile_height = div(resolution.height - margin * (rows + 1), rows)
+
+    input_ids
+    |> Enum.with_index()
+    |> Enum.map(fn {input_id, index} ->
+      column = rem(index, columns)
+      row = div(index, columns)
+
+      rescaler(input_id, opts, %{
+        top: margin + row * (tile_height + margin),
+        left: margin + column * (tile_width + margin),
+        width: tile_width,
+        height: tile_height
+      })
+    end)
+    |> view()
+  end
+
+  @doc """
+  Renders the first input on the whole output and the remaining inputs as smaller overlays
+  stacked in one of the corners.
+  """
+  @spec picture_in_picture(
+          [LiveCompositor.input_id()],
+          resolution(),
+          [picture_in_picture_opt()]
+        ) :: Scene.View.t()
+  def picture_in_picture(input_ids, resolution, opts \\ [])
+
+  def picture_in_picture([], _resolution, _opts), do: view([])
+
+  def picture_in_picture([main_input_id | pip_input_ids], resolution, opts) do
+    corner = Keyword.get(opts, :corner, :bottom_right)
+    margin = Keyword.get(opts, :margin, 20)
+    scale = Keyword.get(opts, :scale, 0.25)
+    pip_width = trunc(resolution.width * scale)
+    pip_height = trunc(resolution.height * scale)
+
+    main =
+      rescaler(main_input_id, opts, %{
+        top: 0,
+        left: 0,
+        width: resolution.width,
+        height: resolution.height
+      })
+
+    pips =
+      pip_input_ids
+      |> Enum.with_index()
+      |> Enum.map(fn {input_id, index} ->
+        vertical_offset = margin + index * (pip_height + margin)
+
+        top =
+          if corner in [:top_left, :top_right],
+            do: vertical_offset,
+            else: resolution.height - vertical_offset - pip_height
+
+        left =
+          if corner in [:top_left, :bottom_left],
+            do: margin,
+            else: resolution.width - margin - pip_width
+
+        rescaler(input_id, opts, %{top: top, left: left, width: pip_width, height: pip_height})
+      end)
+
+    view([main | pips])
+  end
+
+  @doc """
+  Renders the first input (the speaker) above a filmstrip with the remaining inputs.
+  """
+  @spec speaker_focus([LiveCompositor.input_id()], resolution(), [speaker_focus_opt()]) ::
+          Scene.View.t()
+  def speaker_focus(input_ids, resolution, opts \\ [])
+
+  def speaker_focus([], _resolution, _opts), do: view([])
+
+  def speaker_focus([speaker_id], resolution, opts) do
+    picture_in_picture([speaker_id], resolution, opts)
+  end
+
+  def speaker_focus([speaker_id | filmstrip_ids], resolution, opts) do
+    margin = Keyword.get(opts, :margin, 0)
+    filmstrip_height = trunc(resolution.height * Keyword.get(opts, :filmstrip_height, 0.2))
+    speaker_height = resolution.height - filmstrip_height
+    count = length(filmstrip_ids)
+
+    # Items are never wider than 16:9, so filmstrip with only a few inputs is centered
+    # instead of stretched.
+    item_width =
+      min(
+        div(resolution.width - margin * (count + 1), count),
+        div((filmstrip_height - 2 * margin) * 16, 9)
+      )
+
+    item_height = filmstrip_height - 2 * margin
+    filmstrip_width = count * item_width + (count - 1) * margin
+    filmstrip_left = div(resolution.width - filmstrip_width, 2)
+
+    speaker =
+      rescaler(speaker_id, opts, %{
+        top: 0,
+        left: 0,
+        width: resolution.width,
+        height: speaker_height
+      })
+
+    filmstrip =
+      filmstrip_ids
+      |> Enum.with_index()
+      |> Enum.map(fn {input_id, index} ->
+        rescaler(input_id, opts, %{
+          top: speaker_height + margin,
+          left: filmstrip_left + index * (item_width + margin),
+          width: item_width,
+          height: item_height
+        })
+      end)
+
+    view([speaker | filmstrip])
+  end
+
+  @doc """
+  Places inputs next to each other in a single row. Each input gets the same width and
+  the full height of the output.
+  """
+  @spec side_by_side([LiveCompositor.input_id()], resolution(), [split_opt()]) :: Scene.View.t()
+  def side_by_side(input_ids, resolution, opts \\ []) do
+    margin = Keyword.get(opts, :margin, 0)
+    count = max(length(input_ids), 1)
+    item_width = div(resolution.width - margin * (count - 1), count)
+
+    input_ids
+    |> Enum.with_index()
+    |> Enum.map(fn {input_id, index} ->
+      rescaler(input_id, opts, %{
+        top: 0,
+        left: index * (item_width + margin),
+        width: item_width,
+        height: resolution.height
+      })
+    end)
+    |> view()
+  end
+
+  @doc """
+  Places inputs one below another in a single column. Each input gets the same height and
+  the full width of the output.
+  """
+  @spec stacked([LiveCompositor.input_id()], resolution(), [split_opt()]) :: Scene.View.t()
+  def stacked(input_ids, resolution, opts \\ []) do
+    margin = Keyword.get(opts, :margin, 0)
+    count = max(length(input_ids), 1)
+    item_height = div(resolution.height - margin * (count - 1), count)
+
+    input_ids
+    |> Enum.with_index()
+    |> Enum.map(fn {input_id, index} ->
+      rescaler(input_id, opts, %{
+        top: index * (item_height + margin),
+        left: 0,
+        width: resolution.width,
+        height: item_height
+      })
+    end)
+    |> view()
+  end
+
+  @doc """
+  Returns id of a component that wraps the input in layouts generated with the `id_prefix`.
+  """
+  @spec component_id(LiveCompositor.input_id(), String.t()) :: Scene.component_id()
+  def component_id(input_id, id_prefix \\ "layout") do
+    "#{id_prefix}_#{input_id}"
+  end
+
+  defp view(children) do
+    %Scene.View{children: children}
+  end
+
+  defp rescaler(input_id, opts, position) do
+    %Scene.Rescaler{
+      id: component_id(input_id, Keyword.get(opts, :id_prefix, "layout")),
+      mode: Keyword.get(opts, :mode, :fit),
+      transition: Keyword.get(opts, :transition),
+      child: %Scene.InputStream{input_id: input_id}
+    }
+    |> Map.merge(position)
+  end
+end
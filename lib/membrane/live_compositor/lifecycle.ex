@@ -9,6 +9,20 @@ defmodule Membrane.LiveCompositor.Lifecycle do
   - `t:input_playing/0`
   - `t:input_eos/0`
 
+  ### MP4 input
+
+  Inputs registered with `Membrane.LiveCompositor.Request.RegisterMp4Input` don't have pads, so
+  their notifications use `Pad.ref(:video_input, input_id)` for the video track and
+  `Pad.ref(:audio_input, input_id)` for the audio track of the file. Notifications are sent
+  only for tracks that are present in the file. `t:input_registered/0` is sent after the server
+  accepts the request, for the tracks reported by the server or, if the server doesn't report
+  them, declared with `:tracks` field of the request.
+
+  - `t:input_registered/0`
+  - `t:input_delivered/0`
+  - `t:input_playing/0`
+  - `t:input_eos/0`
+
   ### Output pad
 
   - `t:output_registered/0`
@@ -24,13 +38,15 @@ defmodule Membrane.LiveCompositor.Lifecycle do
 
   @typedoc """
   The output pad was linked and the TCP connection was established between pipeline and
-  the compositor instance.
+  the compositor instance. For pads with `transport: :udp` it's sent as soon as the stream is
+  registered in the compositor instance.
   """
   @type output_registered :: :output_registered
 
   @typedoc """
   The input pad was linked and the TCP connection was established between pipeline and
-  the compositor instance.
+  the compositor instance. For pads with `transport: :udp` it's sent as soon as the stream is
+  registered in the compositor instance.
   """
   @type input_registered :: :input_registered
 
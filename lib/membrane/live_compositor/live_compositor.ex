@@ -10,6 +10,11 @@ defmodule Membrane.LiveCompositor do
 
   See `Membrane.LiveCompositor.Lifecycle` for input stream lifecycle notifications.
 
+  MP4 files can also be read directly by the LiveCompositor server, without linking any pads.
+  Such inputs are registered with
+  [`Request.RegisterMp4Input`](`Membrane.LiveCompositor.Request.RegisterMp4Input`) and share
+  the `input_id` namespace with input pads.
+
   ## Output streams
 
   Each output pad has a format `Pad.ref(:video_output, output_id)` or `Pad.ref(:audio_output, output_id)`,
@@ -32,12 +37,11 @@ defmodule Membrane.LiveCompositor do
 
   ```
   scene_update_request =  %Request.UpdateVideoOutput{
-    output_id: "output_0"
-    root: %{
-      type: :tiles,
+    output_id: "output_0",
+    root: %Scene.Tiles{
       children: [
-        { type: "input_stream", input_id: "input_0" },
-        { type: "input_stream", input_id: "input_1" }
+        %Scene.InputStream{input_id: "input_0"},
+        %Scene.InputStream{input_id: "input_1"}
       ]
     }
   }
@@ -46,10 +50,14 @@ defmodule Membrane.LiveCompositor do
   ```
 
   `:root` option specifies root component of a scene that will be rendered on the output stream.
-  Concept of a component is explained [here](https://compositor.live/docs/concept/component). For
-  actual component definitions see LiveCompositor documentation e.g.
-  [`View`](https://compositor.live/docs/api/components/View),
-  [`Tiles`](https://compositor.live/docs/api/components/Tiles), ...
+  Concept of a component is explained [here](https://compositor.live/docs/concept/component). All
+  available components are defined in `Membrane.LiveCompositor.Scene` e.g.
+  [`View`](`Membrane.LiveCompositor.Scene.View`),
+  [`Tiles`](`Membrane.LiveCompositor.Scene.Tiles`), ...
+
+  The bin remembers the last scene sent to each output. If `:transition` option is defined in
+  `Request.UpdateVideoOutput`, every component with an `id` that was also present in the previous
+  scene will be smoothly transitioned to its new state.
 
   ## Composition specification - `audio`
 
@@ -80,6 +88,27 @@ defmodule Membrane.LiveCompositor do
   Notification about lifecycle of input/output streams.
   - [`Request.result/0`](`t:Membrane.LiveCompositor.Request.result/0`) - Result of a
   `Membrane.LiveCompositor.Request` sent from the parent process.
+  - [`Timeline.notification/0`](`t:Membrane.LiveCompositor.Timeline.notification/0`) -
+  Notification about cues of a submitted `Membrane.LiveCompositor.Timeline`.
+  - `t:server_down/0` - The LiveCompositor server stopped working, see `on_server_crash` option.
+  - `t:server_restarted/0` - The LiveCompositor server was restarted after a crash, see
+  `on_server_crash` option.
+
+  Requests can also be sent with `request/3`, which returns the result directly to the caller.
+
+  Requests and `:start_composing` received before the bin finishes its setup (i.e. before it
+  connects to the LiveCompositor server) are queued and handled in order once the setup is
+  completed.
+
+  ## Telemetry
+
+  Requests are sent over persistent HTTP connections from a pool shared by all bins. Duration of
+  every request sent to the server is emitted as `[:membrane_live_compositor, :api_request, :stop]`
+  telemetry event with:
+  - `:duration` measurement - Time between sending the request and receiving the response
+  (including retries) in native time units.
+  - `:method`, `:route` and `:lc_address` metadata - Describe the request.
+  - `:result` metadata - `:ok` or `:error`.
 
   ## LiveCompositor documentation
 
@@ -93,21 +122,73 @@ defmodule Membrane.LiveCompositor do
 
   alias Jason.Encoder
 
-  alias Membrane.{Opus, Pad, RemoteStream, RTP, TCP}
+  alias Membrane.{ChildrenSpec, Opus, Pad, RemoteStream, RTP, TCP, UDP}
 
   alias Membrane.LiveCompositor.{
     ApiClient,
     ApiClient.IntoRequest,
     Context,
     Encoder,
+    Error,
     EventHandler,
+    ImageServer,
     Request,
+    RequestPolicy,
     RtcpByeSender,
+    RtpStreamStitcher,
+    RtpVp9Payloader,
+    Scene,
+    SceneTransitions,
+    SceneValidator,
+    ServerMonitor,
     ServerRunner,
     State,
-    StreamsHandler
+    StreamsHandler,
+    Timeline,
+    UdpSource,
+    VideoInputProcessor
   }
 
+  @requests [
+    Request.RegisterImage,
+    Request.RegisterShader,
+    Request.RegisterWebRenderer,
+    Request.RegisterMp4Input,
+    Request.UnregisterImage,
+    Request.UnregisterShader,
+    Request.UnregisterWebRenderer,
+    Request.UnregisterInput,
+    Request.UnregisterOutput,
+    Request.UpdateVideoOutput,
+    Request.UpdateAudioOutput,
+    Request.UpdateOutputs,
+    Request.SubmitTimeline,
+    Request.CancelTimeline,
+    Request.KeyframeRequest
+  ]
+
+  @default_batch_delay Membrane.Time.milliseconds(100)
+
+  # How long the bin waits before connecting again to the WebSocket of the server.
+  @event_handler_reconnect_delay_ms 1_000
+
+  # How long before its time a timeline cue is sent to the server.
+  @timeline_lookahead Membrane.Time.milliseconds(500)
+
+  @udp_jitter_buffer_latency Membrane.Time.milliseconds(200)
+
+  @register_renderer_requests [
+    Request.RegisterImage,
+    Request.RegisterShader,
+    Request.RegisterWebRenderer
+  ]
+
+  @unregister_renderer_requests [
+    Request.UnregisterImage,
+    Request.UnregisterShader,
+    Request.UnregisterWebRenderer
+  ]
+
   @typedoc """
   Input stream id, uniquely identifies an input pad.
   """
@@ -123,11 +204,49 @@ defmodule Membrane.LiveCompositor do
   """
   @type port_range :: {lower_bound :: :inet.port_number(), upper_bound :: :inet.port_number()}
 
+  @typedoc """
+  Codec of the video stream.
+  """
+  @type video_codec :: :h264 | :vp8 | :vp9
+
+  @typedoc """
+  Transport protocol used to exchange RTP streams between the bin and the LiveCompositor server.
+  """
+  @type transport :: :tcp | :udp
+
   @typedoc """
   Supported output sample rates.
   """
   @type output_sample_rate :: 8_000 | 12_000 | 16_000 | 24_000 | 48_000
 
+  @typedoc """
+  Notification sent to the parent when the LiveCompositor server stopped working:
+  - `{:server_exited, exit_reason}` - The server started by the bin exited.
+  - `{:status_check_failed, error}` - The server started outside of the bin didn't respond to
+  several consecutive status checks. `error` is the `Membrane.LiveCompositor.Error` of the last
+  check.
+  - `{:instance_changed, old_instance_id, new_instance_id}` - The server started outside of the
+  bin was restarted, so all inputs, outputs and renderers registered by the bin were lost.
+  - `{:event_handler_exited, exit_reason}` - The WebSocket connection to the server was closed,
+  so the bin stopped receiving events from the server. Not sent in `on_server_crash: :restart`
+  mode, in which the bin connects to the server again.
+  """
+  @type server_down ::
+          {:server_down,
+           {:server_exited, reason :: any()}
+           | {:status_check_failed, Error.t()}
+           | {:instance_changed, old :: String.t(), new :: String.t()}
+           | {:event_handler_exited, reason :: any()}}
+
+  @typedoc """
+  Notification sent to the parent when the session was restored on a restarted LiveCompositor
+  server. `down_at` and `restored_at` are `Membrane.Time.os_time/0` of the moment when the crash
+  was detected and the moment when all streams were registered again. Media produced by the
+  server between these moments is lost.
+  """
+  @type server_restarted ::
+          {:server_restarted, down_at :: Membrane.Time.t(), restored_at :: Membrane.Time.t()}
+
   @typedoc """
   Condition that defines when output stream should end depending on the
   EOS received on inputs.
@@ -206,6 +325,66 @@ defmodule Membrane.LiveCompositor do
                 """,
                 default: :start_locally
               ],
+              web_renderer_enabled?: [
+                spec: boolean(),
+                default: false,
+                description: """
+                Enables web rendering on the LiveCompositor server started by the bin. It allows
+                registering web renderer instances with `Request.RegisterWebRenderer` and using them
+                in the scene with `Scene.WebView` component.
+
+                Web rendering requires a LiveCompositor binary built with web rendering support, e.g.
+                provided with `server_setup: {:start_locally, path}`. When the server is already
+                started, web rendering needs to be enabled when starting it.
+                """
+              ],
+              request_policy: [
+                spec: RequestPolicy.t(),
+                default: %RequestPolicy{},
+                description: """
+                Timeouts and retries of requests sent by the bin to the LiveCompositor server.
+                See `Membrane.LiveCompositor.RequestPolicy` for details.
+                """
+              ],
+              on_server_crash: [
+                spec: :terminate | :notify | :restart,
+                default: :notify,
+                description: """
+                Defines what happens when the LiveCompositor server stops working. Server started
+                by the bin is monitored, server started outside of the bin is periodically checked
+                on its `/status` endpoint. In both cases the parent is notified with
+                `t:server_down/0` and then:
+                - `:terminate` - The bin raises, so it's terminated with an error describing
+                the failure instead of waiting for the streams that will never arrive.
+                - `:notify` - The bin keeps running. Requests sent to the server fail and pads
+                can be unlinked without unregistering them on the server.
+                - `:restart` - The bin starts a new server and restores the session on it:
+                sends `init_requests`, registers renderers and MP4 inputs registered with
+                requests, registers all linked pads with the last scene sent to each output,
+                reconnects the streams and starts composing if it was started before. Afterwards
+                the parent is notified with `t:server_restarted/0`. Lifecycle notifications of
+                the restored streams are sent again. Pending and scheduled cues of timelines are
+                sent again, their times are shifted to the time of the new server. Requests
+                received while the server is restarted are handled after the session is restored.
+                Video inputs are requested to send a keyframe with
+                `Membrane.KeyframeRequestEvent`. Only supported when the server is started by
+                the bin (see `server_setup`).
+                """
+              ],
+              init_requests_failure_mode: [
+                spec: :raise | :notify | :ignore,
+                default: :raise,
+                description: """
+                Defines what happens when one of the `init_requests` fails:
+                - `:raise` - The bin raises, so it crashes during the setup.
+                - `:notify` - The bin logs a warning and sends
+                [`Request.result/0`](`t:Membrane.LiveCompositor.Request.result/0`) notification
+                with the error to the parent.
+                - `:ignore` - The bin logs a warning.
+
+                The remaining requests are sent regardless of the failure.
+                """
+              ],
               init_requests: [
                 spec: list(Request.t()),
                 description: """
@@ -224,9 +403,41 @@ defmodule Membrane.LiveCompositor do
               ]
 
   def_input_pad :video_input,
-    accepted_format: %Membrane.H264{alignment: :nalu, stream_structure: :annexb},
+    accepted_format:
+      any_of(
+        Membrane.H264,
+        Membrane.VP8,
+        Membrane.VP9,
+        Membrane.RawVideo
+      ),
     availability: :on_request,
     options: [
+      video_codec: [
+        spec: video_codec(),
+        default: :h264,
+        description: """
+        Codec of the input stream, it defines which RTP payloader is used by the bin and which
+        decoder is used by the LiveCompositor server. If `:raw_video_encoder` is defined, it's
+        the codec produced by the encoder.
+
+        H264 streams can have any alignment and stream structure, e.g. a stream from MP4 demuxer
+        can be linked directly. Every H264 stream goes through `Membrane.H264.Parser` that converts
+        it to Annex B NAL units before sending it to the server. It's also the case for streams
+        that are already in that format.
+        """
+      ],
+      raw_video_encoder: [
+        spec: Membrane.ChildrenSpec.child_definition() | nil,
+        default: nil,
+        description: """
+        Element used to encode the input stream when it's `Membrane.RawVideo`, e.g.
+        `%Membrane.H264.FFmpeg.Encoder{preset: :ultrafast, tune: :zerolatency}`. It has to
+        produce a stream in the codec defined by `:video_codec`.
+
+        If `nil`, the input stream has to be already encoded, the bin raises when it
+        receives `Membrane.RawVideo`.
+        """
+      ],
       required: [
         spec: boolean(),
         default: false,
@@ -251,10 +462,18 @@ defmodule Membrane.LiveCompositor do
         description: """
         Port number or port range.
 
-        Internally LiveCompositor server communicates with this pipeline locally over RTP.
-        This value defines which TCP ports will be used.
+        Internally LiveCompositor server communicates with this pipeline over RTP.
+        This value defines which TCP or UDP ports will be used by the server.
         """,
         default: {10_000, 60_000}
+      ],
+      transport: [
+        spec: transport(),
+        default: :tcp,
+        description: """
+        Transport protocol of the RTP stream sent to the LiveCompositor server. `:udp` is
+        recommended when the server runs on a remote host connected over a lossy link.
+        """
       ]
     ]
 
@@ -291,27 +510,70 @@ defmodule Membrane.LiveCompositor do
         description: """
         Port number or port range.
 
-        Internally LiveCompositor server communicates with this pipeline locally over RTP.
-        This value defines which TCP ports will be used.
+        Internally LiveCompositor server communicates with this pipeline over RTP.
+        This value defines which TCP or UDP ports will be used by the server.
         """,
         default: {10_000, 60_000}
+      ],
+      transport: [
+        spec: transport(),
+        default: :tcp,
+        description: """
+        Transport protocol of the RTP stream sent to the LiveCompositor server. `:udp` is
+        recommended when the server runs on a remote host connected over a lossy link.
+        """
       ]
     ]
 
   def_output_pad :video_output,
-    accepted_format: %Membrane.H264{alignment: :nalu, stream_structure: :annexb},
+    accepted_format:
+      any_of(
+        %Membrane.H264{alignment: :nalu, stream_structure: :annexb},
+        Membrane.VP8,
+        %Membrane.RawVideo{pixel_format: :I420}
+      ),
     availability: :on_request,
     options: [
+      output_format: [
+        spec: :encoded | :raw_video,
+        default: :encoded,
+        description: """
+        Format of the output stream:
+        - `:encoded` - Stream encoded by the LiveCompositor server with the `:encoder`, i.e.
+        `Membrane.H264` aligned to NAL units or `Membrane.VP8`.
+        - `:raw_video` - `Membrane.RawVideo` in I420 pixel format. The stream encoded by the
+        server is decoded inside the bin. Only supported with `Encoder.FFmpegH264`. It requires
+        `:membrane_h264_ffmpeg_plugin` to be added to the dependencies of your project.
+        """
+      ],
       port: [
         spec: :inet.port_number() | port_range(),
         description: """
         Port number or port range.
 
-        Internally LiveCompositor server communicates with this pipeline locally over RTP.
-        This value defines which TCP ports will be used.
+        Internally LiveCompositor server communicates with this pipeline over RTP.
+        This value defines which ports will be used. With `:tcp` transport, the server listens on
+        a TCP port from this range. With `:udp` transport, the bin listens on a UDP port from
+        this range.
         """,
         default: {10_000, 60_000}
       ],
+      transport: [
+        spec: transport(),
+        default: :tcp,
+        description: """
+        Transport protocol of the RTP stream received from the LiveCompositor server. `:udp` is
+        recommended when the server runs on a remote host connected over a lossy link.
+        """
+      ],
+      jitter_buffer_latency: [
+        spec: Membrane.Time.t() | nil,
+        default: nil,
+        description: """
+        Latency of the jitter buffer that reorders RTP packets received from the server.
+        Defaults to `0` for `:tcp` transport and to 200 milliseconds for `:udp` transport.
+        """
+      ],
       width: [
         spec: non_neg_integer()
       ],
@@ -319,7 +581,10 @@ defmodule Membrane.LiveCompositor do
         spec: non_neg_integer()
       ],
       encoder: [
-        spec: Encoder.FFmpegH264.t()
+        spec: Encoder.FFmpegH264.t() | Encoder.FFmpegVp8.t(),
+        description: """
+        Encoder used by the LiveCompositor server to encode the output stream.
+        """
       ],
       send_eos_when: [
         spec: send_eos_condition(),
@@ -337,17 +602,16 @@ defmodule Membrane.LiveCompositor do
         """
       ],
       initial: [
-        spec: any(),
+        spec: %{root: Scene.component()},
         description: """
         Initial scene that will be rendered on this output.
 
         Example:
         ```
         %{
-          root: %{
-            type: :view,
+          root: %Scene.View{
             children: [
-              %{ type: :input_stream, input_id: "input_0" }
+              %Scene.InputStream{input_id: "input_0"}
             ]
           }
         }
@@ -356,16 +620,13 @@ defmodule Membrane.LiveCompositor do
         To change the scene after the registration you can send `%Request.UpdateVideoOutput{}`
         notification.
 
-        Format of the `:root` field is documented [here](https://compositor.live/docs/concept/component).
-        For specific options see documentation pages for each component e.g.
-        [`View`](https://compositor.live/docs/api/components/View),
-        [`Tiles`](https://compositor.live/docs/api/components/Tiles), ...
+        Components that can be used in the `:root` field are defined in `Membrane.LiveCompositor.Scene`.
         """
       ]
     ]
 
   def_output_pad :audio_output,
-    accepted_format: %RemoteStream{type: :packetized, content_format: Opus},
+    accepted_format: %Opus{self_delimiting?: false},
     availability: :on_request,
     options: [
       port: [
@@ -373,11 +634,29 @@ defmodule Membrane.LiveCompositor do
         description: """
         Port number or port range.
 
-        Internally LiveCompositor server communicates with this pipeline locally over RTP.
-        This value defines which TCP ports will be used.
+        Internally LiveCompositor server communicates with this pipeline over RTP.
+        This value defines which ports will be used. With `:tcp` transport, the server listens on
+        a TCP port from this range. With `:udp` transport, the bin listens on a UDP port from
+        this range.
         """,
         default: {10_000, 60_000}
       ],
+      transport: [
+        spec: transport(),
+        default: :tcp,
+        description: """
+        Transport protocol of the RTP stream received from the LiveCompositor server. `:udp` is
+        recommended when the server runs on a remote host connected over a lossy link.
+        """
+      ],
+      jitter_buffer_latency: [
+        spec: Membrane.Time.t() | nil,
+        default: nil,
+        description: """
+        Latency of the jitter buffer that reorders RTP packets received from the server.
+        Defaults to `0` for `:tcp` transport and to 200 milliseconds for `:udp` transport.
+        """
+      ],
       encoder: [
         spec: Encoder.Opus.t()
       ],
@@ -417,6 +696,83 @@ defmodule Membrane.LiveCompositor do
       ]
     ]
 
+  @doc """
+  Sends a request to the LiveCompositor and waits for the result.
+
+  It's an alternative to sending a request as a notification and waiting for
+  [`Request.result/0`](`t:Membrane.LiveCompositor.Request.result/0`) notification, that is more
+  convenient when the result is needed right away, e.g. when a scene update depends on a
+  previously registered shader.
+
+  First argument can be:
+  - pid of the LiveCompositor bin (in the pipeline it's available in the callback context as
+  `ctx.children[bin_name].pid`). The request is handled by the bin in the same way as a request
+  sent via notification, but the result is returned to the caller instead of being sent to
+  the parent.
+  - address of the LiveCompositor server as `{ip, port}`. The request is sent directly to the
+  server, so it's not tracked by any bin, e.g. scenes are not validated and renderers are not
+  cleaned up on termination. Requests that can only be handled by the bin result in
+  `{:error, :bin_required}`, i.e. `Membrane.LiveCompositor.Request.UpdateOutputs`,
+  `Membrane.LiveCompositor.Request.SubmitTimeline`, `Membrane.LiveCompositor.Request.CancelTimeline`
+  and `Membrane.LiveCompositor.Request.RegisterImage` with `:data`.
+
+  On success, returns decoded body of the server response. On failure, returns the same error as
+  the [`Request.result/0`](`t:Membrane.LiveCompositor.Request.result/0`) notification. If the
+  result arrives after the timeout, it's dropped. The bin replies to requests that are not
+  `Membrane.LiveCompositor.Request` structs with
+  `{:error, %Membrane.LiveCompositor.Error{reason: :unsupported_request}}`.
+  """
+  @spec request(pid() | {:inet.ip_address(), :inet.port_number()}, Request.t(), timeout()) ::
+          {:ok, any()} | {:error, any()}
+  def request(bin_or_address, request, timeout \\ 5_000)
+
+  def request(bin, request, timeout) when is_pid(bin) do
+    # The monitor reference is also an alias that the bin replies to. Alias is deactivated on
+    # demonitor, so a result that arrives after the timeout is dropped instead of staying in
+    # the mailbox of the caller.
+    ref = :erlang.monitor(:process, bin, [{:alias, :demonitor}])
+    send(bin, {:live_compositor_request, ref, ref, request})
+
+    receive do
+      {^ref, result} ->
+        Process.demonitor(ref, [:flush])
+        result
+
+      {:DOWN, ^ref, :process, _pid, reason} ->
+        {:error, %Error{error_code: :transport_error, reason: {:bin_down, reason}}}
+    after
+      timeout ->
+        Process.demonitor(ref, [:flush])
+
+        # Result could have arrived before the alias was deactivated.
+        receive do
+          {^ref, _result} -> :ok
+        after
+          0 -> :ok
+        end
+
+        {:error, %Error{error_code: :transport_error, reason: :timeout}}
+    end
+  end
+
+  def request(lc_address = {_ip, _port}, request, timeout) do
+    if bin_only_request?(request) do
+      {:error, :bin_required}
+    else
+      api_request = IntoRequest.into_request(request)
+      task = Task.async(fn -> ApiClient.send_request(api_request, lc_address) end)
+
+      case Task.yield(task, timeout) || Task.shutdown(task, :brutal_kill) do
+        {:ok, result} -> result
+        nil -> {:error, %Error{error_code: :transport_error, reason: :timeout}}
+      end
+    end
+  end
+
+  @spec bin_only_request?(Request.t()) :: boolean()
+  defp bin_only_request?(%Request.RegisterImage{data: data}), do: data != nil
+  defp bin_only_request?(request), do: IntoRequest.impl_for(request) == nil
+
   @impl true
   def handle_init(_ctx, opt) do
     {[], opt}
@@ -427,34 +783,29 @@ defmodule Membrane.LiveCompositor do
     {:ok, lc_address, server_pid} =
       ServerRunner.ensure_server_started(opt, ctx.utility_supervisor)
 
-    if opt.server_setup != :already_started do
-      Membrane.ResourceGuard.register(
-        ctx.resource_guard,
-        fn -> Process.exit(server_pid, :kill) end,
-        tag: :live_compositor_server
-      )
+    if opt.on_server_crash == :restart and server_pid == nil do
+      raise "on_server_crash: :restart is only supported when the server is started by the bin."
     end
 
-    opt.init_requests
-    |> Enum.each(fn request ->
-      {:ok, _response} =
-        IntoRequest.into_request(request)
-        |> ApiClient.send_request(lc_address)
-    end)
+    guard_server(server_pid, ctx)
+
+    state = %State{
+      options: opt,
+      output_framerate: opt.framerate,
+      output_sample_rate: opt.output_sample_rate,
+      composing_strategy: opt.composing_strategy,
+      lc_address: lc_address,
+      server_pid: server_pid,
+      request_policy: opt.request_policy,
+      api_base_url: ApiClient.base_url(lc_address),
+      on_server_crash: opt.on_server_crash,
+      context: %Context{}
+    }
 
-    Membrane.UtilitySupervisor.start_link_child(
-      ctx.utility_supervisor,
-      {EventHandler, {lc_address, self()}}
-    )
+    {actions, state} = send_init_requests(ctx, state)
+    state = start_server_watchers(ctx, state)
 
-    {[setup: :incomplete],
-     %State{
-       output_framerate: opt.framerate,
-       output_sample_rate: opt.output_sample_rate,
-       composing_strategy: opt.composing_strategy,
-       lc_address: lc_address,
-       context: %Context{}
-     }}
+    {actions ++ [setup: :incomplete], state}
   end
 
   @impl true
@@ -464,27 +815,30 @@ defmodule Membrane.LiveCompositor do
     {:ok, port} =
       StreamsHandler.register_video_input_stream(pad_id, ctx.pad_options, state)
 
+    state = %State{state | pad_ports: Map.put(state.pad_ports, input_ref, port)}
+
     {state, ssrc} = State.next_ssrc(state)
-    {lc_ip, _lc_port} = state.lc_address
+
+    %{video_codec: video_codec, raw_video_encoder: encoder} = ctx.pad_options
 
     links =
       bin_input(input_ref)
-      |> child({:rtp_h264_payloader, pad_id}, RTP.H264.Payloader)
+      |> maybe_encode_raw_video(pad_id, encoder)
+      |> child({:video_input_processor, pad_id}, %VideoInputProcessor{input_id: pad_id})
+      |> maybe_parse_h264(pad_id, video_codec)
+      |> child({:rtp_video_payloader, pad_id}, video_payloader(video_codec))
       |> via_in(:input,
         options: [ssrc: ssrc, payload_type: 96, clock_rate: 90_000]
       )
       |> child({:rtp_sender, pad_id}, RTP.Muxer)
       |> child({:bye_sender, pad_id}, %RtcpByeSender{ssrc: ssrc})
-      |> child({:tcp_encapsulator, pad_id}, RTP.TCP.Encapsulator)
-      |> child({:tcp_sink, input_ref}, %TCP.Sink{
-        connection_side: {:client, lc_ip, port},
-        close_on_eos: false,
-        on_connection_closed: :drop_buffers
-      })
+      |> via_out(Pad.ref(:output, state.server_generation))
+      |> input_transport(input_ref, ctx.pad_options.transport, port, state)
 
     spec = {links, group: input_group_id(pad_id)}
 
-    {[spec: spec], state}
+    {[spec: spec] ++ registered_notification(input_ref, ctx.pad_options.transport, state),
+     state}
   end
 
   @impl true
@@ -494,8 +848,9 @@ defmodule Membrane.LiveCompositor do
     {:ok, port} =
       StreamsHandler.register_audio_input_stream(pad_id, ctx.pad_options, state)
 
+    state = %State{state | pad_ports: Map.put(state.pad_ports, input_ref, port)}
+
     {state, ssrc} = State.next_ssrc(state)
-    {lc_ip, _lc_port} = state.lc_address
 
     links =
       bin_input(input_ref)
@@ -505,119 +860,171 @@ defmodule Membrane.LiveCompositor do
       )
       |> child({:rtp_sender, pad_id}, RTP.Muxer)
       |> child({:bye_sender, pad_id}, %RtcpByeSender{ssrc: ssrc})
-      |> child({:tcp_encapsulator, pad_id}, RTP.TCP.Encapsulator)
-      |> child({:tcp_sink, input_ref}, %TCP.Sink{
-        connection_side: {:client, lc_ip, port},
-        close_on_eos: false,
-        on_connection_closed: :drop_buffers
-      })
+      |> via_out(Pad.ref(:output, state.server_generation))
+      |> input_transport(input_ref, ctx.pad_options.transport, port, state)
 
     spec = {links, group: input_group_id(pad_id)}
 
-    {[spec: spec], state}
+    {[spec: spec] ++ registered_notification(input_ref, ctx.pad_options.transport, state),
+     state}
   end
 
   @impl true
   def handle_pad_added(output_ref = Pad.ref(:video_output, pad_id), ctx, state) do
-    state = %State{state | context: Context.add_stream(output_ref, state.context)}
-    {:ok, port} = StreamsHandler.register_video_output_stream(pad_id, ctx.pad_options, state)
-    {lc_ip, _lc_port} = state.lc_address
-
-    output_stream_format = %Membrane.H264{
-      framerate: state.output_framerate,
-      alignment: :nalu,
-      stream_structure: :annexb,
-      width: ctx.pad_options.width,
-      height: ctx.pad_options.height
+    state = %State{
+      state
+      | context: Context.add_stream(output_ref, state.context),
+        video_scenes: Map.put(state.video_scenes, pad_id, ctx.pad_options.initial.root)
     }
 
+    {udp_port, state} = open_udp_socket(output_ref, ctx.pad_options, state)
+
+    {:ok, port} =
+      StreamsHandler.register_video_output_stream(pad_id, ctx.pad_options, state, udp_port)
+
+    state = %State{state | pad_ports: Map.put(state.pad_ports, output_ref, port)}
+
+    output_stream_format =
+      case ctx.pad_options.encoder do
+        %Encoder.FFmpegH264{} ->
+          %Membrane.H264{
+            framerate: state.output_framerate,
+            alignment: :nalu,
+            stream_structure: :annexb,
+            width: ctx.pad_options.width,
+            height: ctx.pad_options.height
+          }
+
+        %Encoder.FFmpegVp8{} ->
+          %Membrane.VP8{width: ctx.pad_options.width, height: ctx.pad_options.height}
+      end
+
     links =
       [
-        child({:tcp_source, output_ref}, %TCP.Source{
-          connection_side: {:client, lc_ip, port}
-        })
-        |> child({:tcp_decapsulator, pad_id}, RTP.TCP.Decapsulator)
+        output_transport(output_ref, ctx.pad_options.transport, port, state)
+        |> maybe_stitch_rtp(pad_id, 90_000, state)
         |> child({:rtp_receiver, output_ref}, RTP.Demuxer)
         |> via_out(:output, options: [stream_id: {:payload_type, 96}])
-        |> child(%Membrane.RTP.JitterBuffer{latency: 0, clock_rate: 90_000})
-        |> child(RTP.H264.Depayloader)
+        |> child(%Membrane.RTP.JitterBuffer{
+          latency: jitter_buffer_latency(ctx.pad_options),
+          clock_rate: 90_000
+        })
+        |> child(video_depayloader(ctx.pad_options.encoder))
+        |> maybe_parse_h264_output(pad_id, ctx.pad_options.encoder)
         |> child({:output_processor, pad_id}, %Membrane.LiveCompositor.VideoOutputProcessor{
-          output_stream_format: output_stream_format
+          output_stream_format: output_stream_format,
+          notify_pts?: state.composing_strategy == :offline_processing
         })
+        |> maybe_decode_video(pad_id, ctx.pad_options, state)
         |> bin_output(Pad.ref(:video_output, pad_id))
       ]
 
     spec = {links, group: output_group_id(pad_id)}
 
-    {[spec: spec], state}
+    {[spec: spec] ++ registered_notification(output_ref, ctx.pad_options.transport, state),
+     state}
   end
 
   @impl true
   def handle_pad_added(output_ref = Pad.ref(:audio_output, pad_id), ctx, state) do
-    state = %State{state | context: Context.add_stream(output_ref, state.context)}
-    {:ok, port} = StreamsHandler.register_audio_output_stream(pad_id, ctx.pad_options, state)
-    {lc_ip, _lc_port} = state.lc_address
+    state = %State{
+      state
+      | context: Context.add_stream(output_ref, state.context),
+        audio_mixes: Map.put(state.audio_mixes, pad_id, ctx.pad_options.initial.inputs)
+    }
+
+    {udp_port, state} = open_udp_socket(output_ref, ctx.pad_options, state)
+
+    {:ok, port} =
+      StreamsHandler.register_audio_output_stream(pad_id, ctx.pad_options, state, udp_port)
+
+    state = %State{state | pad_ports: Map.put(state.pad_ports, output_ref, port)}
 
     links = [
-      child({:tcp_source, output_ref}, %TCP.Source{
-        connection_side: {:client, lc_ip, port}
-      })
-      |> child({:tcp_decapsulator, pad_id}, RTP.TCP.Decapsulator)
+      output_transport(output_ref, ctx.pad_options.transport, port, state)
+      |> maybe_stitch_rtp(pad_id, 48_000, state)
       |> child({:rtp_receiver, output_ref}, RTP.Demuxer)
       |> via_out(:output, options: [stream_id: {:payload_type, 97}])
-      |> child(%Membrane.RTP.JitterBuffer{latency: 0, clock_rate: 48_000})
+      |> child(%Membrane.RTP.JitterBuffer{
+        latency: jitter_buffer_latency(ctx.pad_options),
+        clock_rate: 48_000
+      })
       |> child(RTP.Opus.Depayloader)
-      |> child({:output_processor, pad_id}, Membrane.LiveCompositor.AudioOutputProcessor)
+      |> child({:output_processor, pad_id}, %Membrane.LiveCompositor.AudioOutputProcessor{
+        channels: Encoder.Opus.channels_count(ctx.pad_options.encoder),
+        notify_pts?: state.composing_strategy == :offline_processing
+      })
       |> bin_output(Pad.ref(:audio_output, pad_id))
     ]
 
     spec = {links, group: output_group_id(pad_id)}
 
-    {[spec: spec], state}
+    {[spec: spec] ++ registered_notification(output_ref, ctx.pad_options.transport, state),
+     state}
   end
 
   @impl true
-  def handle_pad_removed(Pad.ref(input_type, pad_id), _ctx, state)
+  def handle_pad_removed(input_ref = Pad.ref(input_type, pad_id), _ctx, state)
       when input_type in [:audio_input, :video_input] do
-    ensure_input_unregistered(pad_id, state.lc_address)
+    ensure_input_unregistered(pad_id, state)
+
+    state = %State{
+      state
+      | context: Context.remove_input(pad_id, state.context),
+        pad_ports: Map.delete(state.pad_ports, input_ref)
+    }
 
-    state = %State{state | context: Context.remove_input(pad_id, state.context)}
     {[remove_children: input_group_id(pad_id)], state}
   end
 
   @impl true
-  def handle_pad_removed(Pad.ref(output_type, pad_id), _ctx, state)
+  def handle_pad_removed(output_ref = Pad.ref(output_type, pad_id), _ctx, state)
       when output_type in [:audio_output, :video_output] do
-    ensure_output_unregistered(pad_id, state.lc_address)
+    ensure_output_unregistered(pad_id, state)
+    state = close_udp_socket(output_ref, state)
+
+    state = %State{
+      state
+      | context: Context.remove_output(pad_id, state.context),
+        video_scenes: Map.delete(state.video_scenes, pad_id),
+        audio_mixes: Map.delete(state.audio_mixes, pad_id),
+        pending_updates:
+          Map.drop(state.pending_updates, [{:video_scenes, pad_id}, {:audio_mixes, pad_id}]),
+        pad_ports: Map.delete(state.pad_ports, output_ref)
+    }
 
-    state = %State{state | context: Context.remove_output(pad_id, state.context)}
     {[remove_children: output_group_id(pad_id)], state}
   end
 
+  @impl true
+  def handle_parent_notification(notification, _ctx, state = %State{setup_completed?: false}) do
+    {[], %State{state | queued_messages: [{:notification, notification} | state.queued_messages]}}
+  end
+
+  @impl true
+  def handle_parent_notification(
+        notification,
+        _ctx,
+        state = %State{server_down?: true, on_server_crash: :restart}
+      ) do
+    {[], %State{state | queued_messages: [{:notification, notification} | state.queued_messages]}}
+  end
+
   @impl true
   def handle_parent_notification(:start_composing, _ctx, state) do
     {:ok, _response} =
-      ApiClient.start_composing(state.lc_address)
+      ApiClient.start_composing(state.lc_address, api_opts(:internal, state))
 
-    {[], state}
+    {[], composing_started(state)}
   end
 
   @impl true
-  def handle_parent_notification(req = %module{}, _ctx, state)
-      when module in [
-             Request.RegisterImage,
-             Request.RegisterShader,
-             Request.UnregisterImage,
-             Request.UnregisterShader,
-             Request.UnregisterInput,
-             Request.UnregisterOutput,
-             Request.UpdateVideoOutput,
-             Request.UpdateAudioOutput,
-             Request.KeyframeRequest
-           ] do
-    response = handle_request(req, state.lc_address)
+  def handle_parent_notification(req = %module{}, ctx, state) when module in @requests do
+    req = put_ref(req)
+    {response, state} = apply_request(req, ctx, state)
 
-    {[notify_parent: {:request_result, req, response}], state}
+    {[notify_parent: {:request_result, req, response}] ++
+       mp4_input_notifications(req, response, state), state}
   end
 
   @impl true
@@ -632,17 +1039,20 @@ defmodule Membrane.LiveCompositor do
   @impl true
   def handle_child_notification(
         {:connection_info, _ip, _port},
-        {:tcp_sink, pad_ref},
+        {:tcp_sink, pad_ref, generation},
         _ctx,
         state = %State{}
       ) do
-    {[notify_parent: {:input_registered, pad_ref, state.context}], state}
+    keyframe_request =
+      if generation > 0, do: restored_input_keyframe_request(pad_ref), else: []
+
+    {[notify_parent: {:input_registered, pad_ref, state.context}] ++ keyframe_request, state}
   end
 
   @impl true
   def handle_child_notification(
         {:connection_info, _ip, _port},
-        {:tcp_source, pad_ref},
+        {:tcp_source, pad_ref, _generation},
         _ctx,
         state = %State{}
       ) do
@@ -650,8 +1060,46 @@ defmodule Membrane.LiveCompositor do
   end
 
   @impl true
-  def handle_child_notification(:keyframe_request, {:output_processor, pad_id}, _ctx, state) do
-    handle_request(%Request.KeyframeRequest{output_id: pad_id}, state.lc_address)
+  def handle_child_notification(
+        {:udp_socket_owner, source_pid},
+        udp_source = {:udp_source, output_ref, _generation},
+        _ctx,
+        state
+      ) do
+    {udp_socket, udp_sockets} = Map.pop!(state.udp_sockets, output_ref)
+    :ok = :gen_udp.controlling_process(udp_socket, source_pid)
+
+    {[notify_child: {udp_source, :socket_ready}], %State{state | udp_sockets: udp_sockets}}
+  end
+
+  @impl true
+  def handle_child_notification({:pts, pts}, {:output_processor, _pad_id}, _ctx, state) do
+    {[], %State{state | output_pts: max(pts, state.output_pts || pts)}}
+  end
+
+  # Outputs with `output_format: :raw_video` receive keyframe requests in the processor after
+  # the decoder.
+  @impl true
+  def handle_child_notification(:keyframe_request, {processor, pad_id}, _ctx, state)
+      when processor in [:output_processor, :raw_output_processor] do
+    handle_request(%Request.KeyframeRequest{output_id: pad_id}, state)
+
+    {[], state}
+  end
+
+  # The connection with the server is closed both when the output ends and when the server
+  # crashes, so the end of stream is forwarded only after the server monitor confirms that
+  # the server is still working.
+  @impl true
+  def handle_child_notification(
+        {:end_of_stream, Pad.ref(:input, generation)},
+        {:rtp_stitcher, pad_id},
+        _ctx,
+        state
+      ) do
+    if generation == state.server_generation and not state.server_down? do
+      ServerMonitor.confirm_server_up(state.server_monitor, {:end_of_stream, pad_id, generation})
+    end
 
     {[], state}
   end
@@ -665,13 +1113,27 @@ defmodule Membrane.LiveCompositor do
     {[], state}
   end
 
+  # Connection to a server started after the crash (the session is restored in
+  # `restore_session/5`) or a reconnection of the event handler.
   @impl true
-  def handle_info(:websocket_connected, _ctx, state) do
-    if state.composing_strategy == :real_time_auto_init do
-      {:ok, _resp} = ApiClient.start_composing(state.lc_address)
-    end
+  def handle_info(:websocket_connected, _ctx, state = %State{setup_completed?: true}) do
+    {[], state}
+  end
 
-    {[setup: :complete], state}
+  @impl true
+  def handle_info(:websocket_connected, ctx, state) do
+    state =
+      if state.composing_strategy == :real_time_auto_init do
+        {:ok, _resp} =
+          ApiClient.start_composing(state.lc_address, api_opts(:internal, state))
+
+        composing_started(state)
+      else
+        state
+      end
+
+    {actions, state} = handle_queued_messages(ctx, %State{state | setup_completed?: true})
+    {[setup: :complete] ++ actions, state}
   end
 
   @impl true
@@ -679,6 +1141,164 @@ defmodule Membrane.LiveCompositor do
     {[notify_parent: {event_type, event_data, state.context}], state}
   end
 
+  @impl true
+  def handle_info(
+        msg = {:live_compositor_request, _from, _ref, _req},
+        _ctx,
+        state = %State{setup_completed?: false}
+      ) do
+    {[], %State{state | queued_messages: [{:info, msg} | state.queued_messages]}}
+  end
+
+  @impl true
+  def handle_info(
+        msg = {:live_compositor_request, _from, _ref, _req},
+        _ctx,
+        state = %State{server_down?: true, on_server_crash: :restart}
+      ) do
+    {[], %State{state | queued_messages: [{:info, msg} | state.queued_messages]}}
+  end
+
+  @impl true
+  def handle_info({:live_compositor_request, from, ref, req = %module{}}, ctx, state)
+      when module in @requests do
+    {response, state} = apply_request(put_ref(req), ctx, state)
+    send(from, {ref, response})
+
+    {mp4_input_notifications(req, response, state), state}
+  end
+
+  # The caller waits for the result, so it's replied to right away instead of timing out.
+  @impl true
+  def handle_info({:live_compositor_request, from, ref, req}, _ctx, state) do
+    error = %Error{reason: :unsupported_request, message: "Unsupported request: #{inspect(req)}"}
+    send(from, {ref, {:error, error}})
+
+    {[], state}
+  end
+
+  @impl true
+  def handle_info(
+        {:live_compositor_server_down, _reason},
+        _ctx,
+        state = %State{server_down?: true}
+      ) do
+    # Server failure can be reported by both the server monitor and the event handler.
+    {[], state}
+  end
+
+  @impl true
+  def handle_info({:live_compositor_server_down, reason}, ctx, state) do
+    Membrane.Logger.error("LiveCompositor server is down. Reason: #{inspect(reason)}")
+    state = %State{state | server_down?: true}
+
+    case state.on_server_crash do
+      :restart ->
+        :ok = restart_server(Membrane.Time.os_time(), ctx, state)
+        {[notify_parent: {:server_down, reason}], state}
+
+      :terminate ->
+        send(self(), {:terminate_on_server_down, reason})
+        {[notify_parent: {:server_down, reason}], state}
+
+      :notify ->
+        {[notify_parent: {:server_down, reason}], state}
+    end
+  end
+
+  @impl true
+  def handle_info(
+        {:live_compositor_server_up, {:end_of_stream, pad_id, generation}},
+        ctx,
+        state
+      ) do
+    stitcher = {:rtp_stitcher, pad_id}
+
+    if generation == state.server_generation and not state.server_down? and
+         Map.has_key?(ctx.children, stitcher) do
+      {[notify_child: {stitcher, :end_of_stream}], state}
+    else
+      {[], state}
+    end
+  end
+
+  @impl true
+  def handle_info({:DOWN, ref, :process, _pid, reason}, ctx, state)
+      when ref == state.event_handler_monitor do
+    state = %State{state | event_handler_monitor: nil}
+
+    case state.on_server_crash do
+      :restart ->
+        Membrane.Logger.warning(
+          "LiveCompositor event handler exited, reconnecting. Reason: #{inspect(reason)}"
+        )
+
+        # The event handler also exits when the server crashes. In that case it's started
+        # again after the server is restarted and the reconnection is skipped.
+        Process.send_after(self(), :reconnect_event_handler, @event_handler_reconnect_delay_ms)
+        {[], state}
+
+      _terminate_or_notify ->
+        handle_info({:live_compositor_server_down, {:event_handler_exited, reason}}, ctx, state)
+    end
+  end
+
+  @impl true
+  def handle_info(
+        {:live_compositor_server_restarted, down_at, lc_address, server_pid},
+        ctx,
+        state
+      ) do
+    restore_session(down_at, lc_address, server_pid, ctx, state)
+  end
+
+  @impl true
+  def handle_info(:reconnect_event_handler, ctx, state) do
+    if state.event_handler_monitor != nil or state.server_down? do
+      {[], state}
+    else
+      case start_event_handler(ctx, state) do
+        {:ok, event_handler_monitor} ->
+          {[], %State{state | event_handler_monitor: event_handler_monitor}}
+
+        {:error, reason} ->
+          Membrane.Logger.warning(
+            "LiveCompositor event handler failed to reconnect. Reason: #{inspect(reason)}"
+          )
+
+          Process.send_after(self(), :reconnect_event_handler, @event_handler_reconnect_delay_ms)
+          {[], state}
+      end
+    end
+  end
+
+  @impl true
+  def handle_info({:terminate_on_server_down, reason}, _ctx, _state) do
+    raise """
+    LiveCompositor server is down, terminating the bin. Set `on_server_crash: :notify` option \
+    to keep the bin running. Reason: #{inspect(reason)}.
+    """
+  end
+
+  # Timelines are rebased and run again once the session is restored.
+  @impl true
+  def handle_info(
+        {:timeline_tick, _timeline_id},
+        _ctx,
+        state = %State{server_down?: true, on_server_crash: :restart}
+      ) do
+    {[], state}
+  end
+
+  @impl true
+  def handle_info({:timeline_tick, timeline_id}, ctx, state) do
+    if Map.has_key?(state.timelines, timeline_id) do
+      run_timeline(timeline_id, ctx, state)
+    else
+      {[], state}
+    end
+  end
+
   @impl true
   def handle_info(msg, _ctx, state) do
     Membrane.Logger.debug("Unknown msg received: #{inspect(msg)}")
@@ -691,18 +1311,106 @@ defmodule Membrane.LiveCompositor do
     {[], state}
   end
 
-  @spec ensure_input_unregistered(input_id(), {:inet.ip_address(), :inet.port_number()}) :: :ok
-  defp ensure_input_unregistered(input_id, lc_address) do
+  @spec guard_server(pid() | nil, Membrane.Bin.CallbackContext.t()) :: :ok
+  defp guard_server(nil, _ctx), do: :ok
+
+  defp guard_server(server_pid, ctx) do
+    Membrane.ResourceGuard.register(
+      ctx.resource_guard,
+      fn -> Process.exit(server_pid, :kill) end,
+      tag: :live_compositor_server
+    )
+  end
+
+  @spec send_init_requests(Membrane.Bin.CallbackContext.t(), State.t()) ::
+          {[Membrane.Bin.Action.t()], State.t()}
+  defp send_init_requests(ctx, state) do
+    %{init_requests: requests, init_requests_failure_mode: failure_mode} = state.options
+
+    Enum.flat_map_reduce(requests, state, fn request, state ->
+      request = put_ref(request)
+      {sent_request, state} = maybe_serve_image_data(request, ctx, state)
+
+      response =
+        IntoRequest.into_request(sent_request)
+        |> ApiClient.send_request(state.lc_address, api_opts(request, state))
+
+      state = track_renderers(request, response, ctx.resource_guard, state)
+      state = track_mp4_inputs(request, response, ctx.resource_guard, state)
+
+      {init_request_actions(request, response, failure_mode) ++
+         mp4_input_notifications(request, response, state), state}
+    end)
+  end
+
+  # Requests received before the setup was completed or while the server was restarted are
+  # handled in the order they arrived.
+  @spec handle_queued_messages(Membrane.Bin.CallbackContext.t(), State.t()) ::
+          {[Membrane.Bin.Action.t()], State.t()}
+  defp handle_queued_messages(ctx, state) do
+    queued_messages = Enum.reverse(state.queued_messages)
+    state = %State{state | queued_messages: []}
+
+    Enum.flat_map_reduce(queued_messages, state, fn
+      {:notification, notification}, state ->
+        handle_parent_notification(notification, ctx, state)
+
+      {:info, msg}, state ->
+        handle_info(msg, ctx, state)
+    end)
+  end
+
+  # Every request handled by the bin has a ref, so that its result can be matched with it.
+  @spec put_ref(Request.t()) :: Request.t()
+  defp put_ref(req = %{ref: nil}), do: %{req | ref: make_ref()}
+  defp put_ref(req), do: req
+
+  @spec start_server_watchers(Membrane.Bin.CallbackContext.t(), State.t()) :: State.t()
+  defp start_server_watchers(ctx, state) do
+    {:ok, event_handler_monitor} = start_event_handler(ctx, state)
+
+    {:ok, server_monitor} =
+      Membrane.UtilitySupervisor.start_link_child(
+        ctx.utility_supervisor,
+        {ServerMonitor, {state.lc_address, state.server_pid, self()}}
+      )
+
+    %State{state | event_handler_monitor: event_handler_monitor, server_monitor: server_monitor}
+  end
+
+  # The WebSocket connection is closed when the server crashes, so the event handler isn't linked
+  # with the bin. It's monitored instead and its exit is handled according to `on_server_crash`.
+  @spec start_event_handler(Membrane.Bin.CallbackContext.t(), State.t()) ::
+          {:ok, reference()} | {:error, any()}
+  defp start_event_handler(ctx, state) do
+    if state.event_handler_monitor != nil,
+      do: Process.demonitor(state.event_handler_monitor, [:flush])
+
+    child_spec =
+      Supervisor.child_spec({EventHandler, {state.lc_address, self()}}, restart: :temporary)
+
+    case Membrane.UtilitySupervisor.start_child(ctx.utility_supervisor, child_spec) do
+      {:ok, event_handler} -> {:ok, Process.monitor(event_handler)}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @spec ensure_input_unregistered(input_id(), State.t()) :: :ok
+  defp ensure_input_unregistered(_input_id, %State{server_down?: true}), do: :ok
+
+  defp ensure_input_unregistered(input_id, state) do
+    request = %Request.UnregisterInput{input_id: input_id}
+
     response =
-      %Request.UnregisterInput{input_id: input_id}
+      request
       |> IntoRequest.into_request()
-      |> ApiClient.send_request(lc_address)
+      |> ApiClient.send_request(state.lc_address, api_opts(request, state))
 
     case response do
       {:ok, _response} ->
         :ok
 
-      {:error, {:response, %Req.Response{body: %{"error_code" => "INPUT_STREAM_NOT_FOUND"}}}} ->
+      {:error, %Error{error_code: :input_stream_not_found}} ->
         :ok
 
       {:error, error} ->
@@ -710,18 +1418,22 @@ defmodule Membrane.LiveCompositor do
     end
   end
 
-  @spec ensure_output_unregistered(output_id(), {:inet.ip_address(), :inet.port_number()}) :: :ok
-  defp ensure_output_unregistered(output_id, lc_address) do
+  @spec ensure_output_unregistered(output_id(), State.t()) :: :ok
+  defp ensure_output_unregistered(_output_id, %State{server_down?: true}), do: :ok
+
+  defp ensure_output_unregistered(output_id, state) do
+    request = %Request.UnregisterOutput{output_id: output_id}
+
     response =
-      %Request.UnregisterOutput{output_id: output_id}
+      request
       |> IntoRequest.into_request()
-      |> ApiClient.send_request(lc_address)
+      |> ApiClient.send_request(state.lc_address, api_opts(request, state))
 
     case response do
       {:ok, _response} ->
         :ok
 
-      {:error, {:response, %Req.Response{body: %{"error_code" => "OUTPUT_STREAM_NOT_FOUND"}}}} ->
+      {:error, %Error{error_code: :output_stream_not_found}} ->
         :ok
 
       {:error, error} ->
@@ -729,6 +1441,188 @@ defmodule Membrane.LiveCompositor do
     end
   end
 
+  @spec maybe_encode_raw_video(
+          ChildrenSpec.builder(),
+          input_id(),
+          ChildrenSpec.child_definition() | nil
+        ) :: ChildrenSpec.builder()
+  defp maybe_encode_raw_video(builder, _pad_id, nil), do: builder
+
+  defp maybe_encode_raw_video(builder, pad_id, encoder) do
+    builder
+    |> child({:raw_video_encoder, pad_id}, encoder)
+  end
+
+  # RTP payloader accepts only Annex B NAL units, parser converts H264 in any alignment and
+  # stream structure. The stream format is not known when the pad is linked, so the parser is
+  # added to every H264 input, even if the stream is already in the right format.
+  @spec maybe_parse_h264(ChildrenSpec.builder(), input_id(), video_codec()) ::
+          ChildrenSpec.builder()
+  defp maybe_parse_h264(builder, pad_id, :h264) do
+    builder
+    |> child({:h264_input_parser, pad_id}, %Membrane.H264.Parser{
+      output_alignment: :nalu,
+      output_stream_structure: :annexb
+    })
+  end
+
+  defp maybe_parse_h264(builder, _pad_id, _video_codec), do: builder
+
+  @spec video_payloader(video_codec()) :: module()
+  defp video_payloader(:h264), do: RTP.H264.Payloader
+  defp video_payloader(:vp8), do: RTP.VP8.Payloader
+  defp video_payloader(:vp9), do: RtpVp9Payloader
+
+  @spec video_depayloader(Encoder.FFmpegH264.t() | Encoder.FFmpegVp8.t()) :: module()
+  defp video_depayloader(%Encoder.FFmpegH264{}), do: RTP.H264.Depayloader
+  defp video_depayloader(%Encoder.FFmpegVp8{}), do: RTP.VP8.Depayloader
+
+  # Parser reads profile and resolution from SPS and sends new stream format whenever
+  # SPS changes.
+  @spec maybe_parse_h264_output(
+          ChildrenSpec.builder(),
+          output_id(),
+          Encoder.FFmpegH264.t() | Encoder.FFmpegVp8.t()
+        ) :: ChildrenSpec.builder()
+  defp maybe_parse_h264_output(builder, pad_id, %Encoder.FFmpegH264{}) do
+    builder
+    |> child({:h264_output_parser, pad_id}, %Membrane.H264.Parser{
+      output_alignment: :nalu,
+      output_stream_structure: :annexb
+    })
+  end
+
+  defp maybe_parse_h264_output(builder, _pad_id, _encoder), do: builder
+
+  @spec maybe_decode_video(ChildrenSpec.builder(), output_id(), map(), State.t()) ::
+          ChildrenSpec.builder()
+  defp maybe_decode_video(builder, _pad_id, %{output_format: :encoded}, _state), do: builder
+
+  defp maybe_decode_video(builder, pad_id, pad_options = %{output_format: :raw_video}, state) do
+    unless match?(%Encoder.FFmpegH264{}, pad_options.encoder) do
+      raise "Output format :raw_video is only supported with #{inspect(Encoder.FFmpegH264)}."
+    end
+
+    unless Code.ensure_loaded?(Membrane.H264.FFmpeg.Decoder) do
+      raise """
+      Output format :raw_video requires :membrane_h264_ffmpeg_plugin. Add it to the \
+      dependencies of your project.
+      """
+    end
+
+    # Decoder doesn't know the framerate of the stream, so the stream format
+    # is overridden in the same way as for H264.
+    output_stream_format = %Membrane.RawVideo{
+      width: pad_options.width,
+      height: pad_options.height,
+      framerate: state.output_framerate,
+      pixel_format: :I420,
+      aligned: true
+    }
+
+    # Decoder accepts only streams aligned to access units.
+    builder
+    |> child({:output_au_parser, pad_id}, %Membrane.H264.Parser{output_alignment: :au})
+    |> child({:output_decoder, pad_id}, Membrane.H264.FFmpeg.Decoder)
+    |> child({:raw_output_processor, pad_id}, %Membrane.LiveCompositor.VideoOutputProcessor{
+      output_stream_format: output_stream_format
+    })
+  end
+
+  @spec input_transport(
+          ChildrenSpec.builder(),
+          Pad.ref(),
+          transport(),
+          :inet.port_number(),
+          State.t()
+        ) :: ChildrenSpec.builder()
+  defp input_transport(builder, input_ref = Pad.ref(_input_type, pad_id), :tcp, port, state) do
+    {lc_ip, _lc_port} = state.lc_address
+
+    builder
+    |> child({:tcp_encapsulator, pad_id, state.server_generation}, RTP.TCP.Encapsulator)
+    |> child({:tcp_sink, input_ref, state.server_generation}, %TCP.Sink{
+      connection_side: {:client, lc_ip, port},
+      close_on_eos: false,
+      on_connection_closed: :drop_buffers
+    })
+  end
+
+  defp input_transport(builder, input_ref, :udp, port, state) do
+    {lc_ip, _lc_port} = state.lc_address
+
+    builder
+    |> child({:udp_sink, input_ref}, %UDP.Sink{
+      destination_address: lc_ip,
+      destination_port_no: port
+    })
+  end
+
+  @spec output_transport(Pad.ref(), transport(), :inet.port_number(), State.t()) ::
+          ChildrenSpec.builder()
+  defp output_transport(output_ref = Pad.ref(_output_type, pad_id), :tcp, port, state) do
+    {lc_ip, _lc_port} = state.lc_address
+
+    child({:tcp_source, output_ref, state.server_generation}, %TCP.Source{
+      connection_side: {:client, lc_ip, port}
+    })
+    |> child({:tcp_decapsulator, pad_id, state.server_generation}, RTP.TCP.Decapsulator)
+  end
+
+  defp output_transport(output_ref, :udp, _port, state) do
+    udp_socket = Map.fetch!(state.udp_sockets, output_ref)
+    child({:udp_source, output_ref, state.server_generation}, %UdpSource{socket: udp_socket})
+  end
+
+  @spec open_udp_socket(Pad.ref(), map(), State.t()) :: {:inet.port_number() | nil, State.t()}
+  defp open_udp_socket(output_ref, %{transport: :udp, port: port}, state) do
+    {:ok, udp_socket, udp_port} = StreamsHandler.open_udp_socket(port)
+    {udp_port, %State{state | udp_sockets: Map.put(state.udp_sockets, output_ref, udp_socket)}}
+  end
+
+  defp open_udp_socket(_output_ref, _pad_options, state), do: {nil, state}
+
+  # Socket is still kept by the bin if the UDP source didn't start.
+  @spec close_udp_socket(Pad.ref(), State.t()) :: State.t()
+  defp close_udp_socket(output_ref, state) do
+    {udp_socket, udp_sockets} = Map.pop(state.udp_sockets, output_ref)
+    if udp_socket != nil, do: :gen_udp.close(udp_socket)
+    %State{state | udp_sockets: udp_sockets}
+  end
+
+  @spec maybe_stitch_rtp(ChildrenSpec.builder(), output_id(), pos_integer(), State.t()) ::
+          ChildrenSpec.builder()
+  defp maybe_stitch_rtp(builder, pad_id, clock_rate, state = %State{on_server_crash: :restart}) do
+    builder
+    |> via_in(Pad.ref(:input, state.server_generation))
+    |> child({:rtp_stitcher, pad_id}, %RtpStreamStitcher{clock_rate: clock_rate})
+  end
+
+  defp maybe_stitch_rtp(builder, _pad_id, _clock_rate, _state), do: builder
+
+  # Over TCP, the stream is registered when the connection is established (see
+  # `handle_child_notification/4`). UDP is connectionless, so the stream is registered
+  # right away.
+  @spec registered_notification(Pad.ref(), transport(), State.t()) :: [
+          Membrane.Bin.Action.t()
+        ]
+  defp registered_notification(_pad_ref, :tcp, _state), do: []
+
+  defp registered_notification(pad_ref = Pad.ref(pad_type, _pad_id), :udp, state) do
+    notification =
+      case pad_type do
+        type when type in [:video_input, :audio_input] -> :input_registered
+        type when type in [:video_output, :audio_output] -> :output_registered
+      end
+
+    [notify_parent: {notification, pad_ref, state.context}]
+  end
+
+  @spec jitter_buffer_latency(map()) :: Membrane.Time.t()
+  defp jitter_buffer_latency(%{jitter_buffer_latency: latency}) when latency != nil, do: latency
+  defp jitter_buffer_latency(%{transport: :tcp}), do: 0
+  defp jitter_buffer_latency(%{transport: :udp}), do: @udp_jitter_buffer_latency
+
   @spec input_group_id(input_id()) :: String.t()
   defp input_group_id(input_id) do
     "input_group_#{input_id}"
@@ -739,17 +1633,636 @@ defmodule Membrane.LiveCompositor do
     "output_group_#{output_id}"
   end
 
-  @spec handle_request(Request.t(), {:inet.ip_address(), :inet.port_number()}) ::
-          ApiClient.request_result()
-  defp handle_request(req, lc_address) do
+  @spec apply_request(Request.t(), Membrane.Bin.CallbackContext.t(), State.t()) ::
+          {ApiClient.request_result() | {:error, any()}, State.t()}
+  defp apply_request(req = %Request.UpdateVideoOutput{}, _ctx, state) do
+    state = promote_pending_updates(state)
+
+    case prepare_scene(req, state) do
+      {:ok, root} ->
+        update_output(%Request.UpdateVideoOutput{req | root: root}, state)
+
+      {:error, reasons} ->
+        Membrane.Logger.warning(
+          "LiveCompositor rejected invalid scene: #{inspect(req)}.\nReasons: #{inspect(reasons)}."
+        )
+
+        {{:error, {:invalid_scene, reasons}}, state}
+    end
+  end
+
+  defp apply_request(req = %Request.UpdateAudioOutput{}, _ctx, state) do
+    update_output(req, state)
+  end
+
+  defp apply_request(req = %Request.UpdateOutputs{}, _ctx, state) do
+    state = promote_pending_updates(state)
+
+    {updates, invalid_scene_reasons} =
+      Enum.map_reduce(req.updates, [], fn
+        update = %Request.UpdateVideoOutput{}, reasons ->
+          case prepare_scene(update, state) do
+            {:ok, root} -> {%Request.UpdateVideoOutput{update | root: root}, reasons}
+            {:error, update_reasons} -> {update, reasons ++ update_reasons}
+          end
+
+        update = %Request.UpdateAudioOutput{}, reasons ->
+          {update, reasons}
+      end)
+
+    with [] <- invalid_scene_reasons,
+         {:ok, schedule_time} <- batch_schedule_time(req, state) do
+      {results, state} =
+        Enum.map_reduce(updates, state, fn update, state ->
+          update_output(%{update | schedule_time: schedule_time}, state)
+        end)
+
+      cond do
+        not Enum.all?(results, &match?({:ok, _body}, &1)) ->
+          {{:error, {:update_outputs_failed, results}}, state}
+
+        schedule_time_passed?(schedule_time, state) ->
+          {{:error, {:schedule_time_missed, results}}, state}
+
+        true ->
+          {{:ok, results}, state}
+      end
+    else
+      {:error, reason} ->
+        {{:error, reason}, state}
+
+      invalid_scene_reasons ->
+        Membrane.Logger.warning("""
+        LiveCompositor rejected invalid scene: #{inspect(req)}.
+        Reasons: #{inspect(invalid_scene_reasons)}.\
+        """)
+
+        {{:error, {:invalid_scene, invalid_scene_reasons}}, state}
+    end
+  end
+
+  defp apply_request(%Request.SubmitTimeline{timeline: timeline}, _ctx, state) do
+    case Enum.reject(timeline.cues, &Timeline.cue_request?(&1.request)) do
+      [] ->
+        submit_timeline(timeline, state)
+
+      invalid_cues ->
+        {{:error, {:invalid_cues, Enum.map(invalid_cues, & &1.id)}}, state}
+    end
+  end
+
+  defp apply_request(req = %Request.CancelTimeline{}, _ctx, state) do
+    case Map.fetch(state.timelines, req.timeline_id) do
+      {:ok, entry} ->
+        cues =
+          entry.cues
+          |> Map.reject(fn {cue_id, %{status: status}} ->
+            status == :pending and
+              (req.cue_ids == nil or Timeline.cue_matches?(cue_id, req.cue_ids))
+          end)
+
+        state = %State{
+          state
+          | timelines: Map.put(state.timelines, req.timeline_id, %{entry | cues: cues})
+        }
+
+        {{:ok, cue_statuses(cues)}, state}
+
+      :error ->
+        {{:error, {:unknown_timeline, req.timeline_id}}, state}
+    end
+  end
+
+  defp apply_request(req = %module{}, ctx, state) when module in @register_renderer_requests do
+    renderer = renderer_ref(req)
+
+    if Context.renderer_registered?(renderer, state.context) do
+      Membrane.Logger.warning(
+        "LiveCompositor rejected request: #{inspect(req)}. Renderer is already registered."
+      )
+
+      {{:error, {:already_registered, renderer}}, state}
+    else
+      {sent_req, state} = maybe_serve_image_data(req, ctx, state)
+      response = handle_request(sent_req, state)
+      state = track_renderers(req, response, ctx.resource_guard, state)
+
+      {response, state}
+    end
+  end
+
+  defp apply_request(req = %Request.RegisterMp4Input{input_id: input_id}, ctx, state) do
+    if Context.input_registered?(input_id, state.context) do
+      Membrane.Logger.warning(
+        "LiveCompositor rejected request: #{inspect(req)}. Input is already registered."
+      )
+
+      {{:error, {:already_registered, {:input, input_id}}}, state}
+    else
+      response = handle_request(req, state)
+      state = track_mp4_inputs(req, response, ctx.resource_guard, state)
+
+      {response, state}
+    end
+  end
+
+  defp apply_request(req, ctx, state) do
+    response = handle_request(req, state)
+    state = track_renderers(req, response, ctx.resource_guard, state)
+    state = track_mp4_inputs(req, response, ctx.resource_guard, state)
+
+    {response, state}
+  end
+
+  @spec prepare_scene(Request.UpdateVideoOutput.t(), State.t()) ::
+          {:ok, Scene.component()} | {:error, [Scene.validation_error()]}
+  defp prepare_scene(req, state) do
+    previous_root = output_state_at(:video_scenes, req.output_id, req.schedule_time, state)
+    root = SceneTransitions.inject(req.root, previous_root, req.transition)
+
+    with :ok <- SceneValidator.validate(root, state) do
+      {:ok, root}
+    end
+  end
+
+  # Sends an update with a scene that was already prepared with `prepare_scene/2`.
+  @spec update_output(
+          Request.UpdateVideoOutput.t() | Request.UpdateAudioOutput.t(),
+          State.t()
+        ) :: {ApiClient.request_result(), State.t()}
+  defp update_output(req = %Request.UpdateVideoOutput{}, state) do
+    response = handle_request(req, state)
+
+    state =
+      case response do
+        {:ok, _response} ->
+          put_output_state(:video_scenes, req.output_id, req.root, req.schedule_time, state)
+
+        {:error, _error} ->
+          state
+      end
+
+    {response, state}
+  end
+
+  defp update_output(req = %Request.UpdateAudioOutput{}, state) do
+    response = handle_request(req, state)
+
+    state =
+      case response do
+        {:ok, _response} ->
+          put_output_state(:audio_mixes, req.output_id, req.inputs, req.schedule_time, state)
+
+        {:error, _error} ->
+          state
+      end
+
+    {response, state}
+  end
+
+  # Scene or audio mix of an update scheduled in the future is kept as pending until its
+  # schedule time, the previous one is still used by the server until then.
+  @spec put_output_state(
+          :video_scenes | :audio_mixes,
+          output_id(),
+          Scene.component() | [Request.UpdateAudioOutput.input()],
+          Membrane.Time.t() | nil,
+          State.t()
+        ) :: State.t()
+  defp put_output_state(field, output_id, value, schedule_time, state) do
+    if schedule_time == nil or schedule_time_passed?(schedule_time, state) do
+      Map.update!(state, field, &Map.put(&1, output_id, value))
+    else
+      pending_updates =
+        Map.update(
+          state.pending_updates,
+          {field, output_id},
+          [{schedule_time, value}],
+          &Enum.sort_by([{schedule_time, value} | &1], fn {time, _value} -> time end)
+        )
+
+      %State{state | pending_updates: pending_updates}
+    end
+  end
+
+  # Scene or audio mix that will be used by the server at `schedule_time`, `nil` stands for now.
+  @spec output_state_at(
+          :video_scenes | :audio_mixes,
+          output_id(),
+          Membrane.Time.t() | nil,
+          State.t()
+        ) :: Scene.component() | [Request.UpdateAudioOutput.input()] | nil
+  defp output_state_at(field, output_id, schedule_time, state) do
+    pending =
+      if schedule_time == nil,
+        do: [],
+        else: Map.get(state.pending_updates, {field, output_id}, [])
+
+    case Enum.take_while(pending, fn {time, _value} -> time <= schedule_time end) do
+      [] -> state |> Map.fetch!(field) |> Map.get(output_id)
+      updates -> updates |> List.last() |> elem(1)
+    end
+  end
+
+  # Moves scenes and audio mixes of the updates whose schedule time has passed from pending
+  # updates to the current ones.
+  @spec promote_pending_updates(State.t()) :: State.t()
+  defp promote_pending_updates(state) do
+    Enum.reduce(
+      state.pending_updates,
+      %State{state | pending_updates: %{}},
+      fn {key = {field, output_id}, updates}, state ->
+        {passed, pending} =
+          Enum.split_while(updates, fn {time, _value} -> schedule_time_passed?(time, state) end)
+
+        state =
+          case List.last(passed) do
+            nil -> state
+            {_time, value} -> Map.update!(state, field, &Map.put(&1, output_id, value))
+          end
+
+        if pending == [],
+          do: state,
+          else: %State{state | pending_updates: Map.put(state.pending_updates, key, pending)}
+      end
+    )
+  end
+
+  # All updates of a batch are scheduled at the same time, so they take effect on the same
+  # frame. Before the composing starts no frame was rendered, so they are scheduled at its
+  # start. In offline processing the server doesn't follow the real time clock, so the time is
+  # counted from the last PTS received on the outputs.
+  @spec batch_schedule_time(Request.UpdateOutputs.t(), State.t()) ::
+          {:ok, Membrane.Time.t()} | {:error, {:schedule_time_passed, Membrane.Time.t()}}
+  defp batch_schedule_time(%Request.UpdateOutputs{schedule_time: nil, delay: delay}, state) do
+    delay = delay || @default_batch_delay
+
+    cond do
+      state.composing_started_at == nil -> {:ok, 0}
+      state.composing_strategy == :offline_processing -> {:ok, (state.output_pts || 0) + delay}
+      true -> {:ok, composing_time(state) + delay}
+    end
+  end
+
+  defp batch_schedule_time(%Request.UpdateOutputs{schedule_time: schedule_time}, state) do
+    if schedule_time_passed?(schedule_time, state),
+      do: {:error, {:schedule_time_passed, schedule_time}},
+      else: {:ok, schedule_time}
+  end
+
+  # Update that reaches the server after its schedule time is applied on a later frame than
+  # the others. Progress of the offline processing is known only from the output PTS, which
+  # is not updated while the bin waits for the responses.
+  @spec schedule_time_passed?(Membrane.Time.t(), State.t()) :: boolean()
+  defp schedule_time_passed?(schedule_time, state) do
+    cond do
+      state.composing_started_at == nil -> false
+      state.composing_strategy == :offline_processing -> schedule_time <= (state.output_pts || 0)
+      true -> schedule_time <= composing_time(state)
+    end
+  end
+
+  @spec composing_started(State.t()) :: State.t()
+  defp composing_started(state) do
+    Enum.each(Map.keys(state.timelines), &send(self(), {:timeline_tick, &1}))
+    %State{state | composing_started_at: Membrane.Time.monotonic_time()}
+  end
+
+  # Current time of the compositor, measured the same way as `schedule_time` of requests.
+  # In offline processing it doesn't depend on the real time clock, so it's always 0.
+  @spec composing_time(State.t()) :: Membrane.Time.t()
+  defp composing_time(state) do
+    if state.composing_started_at == nil or state.composing_strategy == :offline_processing do
+      0
+    else
+      Membrane.Time.monotonic_time() - state.composing_started_at
+    end
+  end
+
+  @spec submit_timeline(Timeline.t(), State.t()) ::
+          {{:ok, %{Timeline.cue_id() => Timeline.cue_status()}}, State.t()}
+  defp submit_timeline(timeline, state) do
+    start =
+      case timeline.start do
+        :now -> composing_time(state)
+        start -> start
+      end
+
+    entry = Map.get(state.timelines, timeline.id, %{cues: %{}, timer: nil})
+
+    cues =
+      Enum.reduce(timeline.cues, entry.cues, fn cue, cues ->
+        case Map.get(cues, cue.id) do
+          existing when existing == nil or existing.status == :pending ->
+            cue = %Timeline.Cue{cue | at: start + cue.at}
+            Map.put(cues, cue.id, %{cue: cue, status: :pending})
+
+          _sent ->
+            cues
+        end
+      end)
+
+    timelines = Map.put(state.timelines, timeline.id, %{entry | cues: cues})
+    state = %State{state | timelines: timelines}
+    send(self(), {:timeline_tick, timeline.id})
+
+    {{:ok, cue_statuses(cues)}, state}
+  end
+
+  @spec run_timeline(Timeline.id(), Membrane.Bin.CallbackContext.t(), State.t()) ::
+          Membrane.Bin.callback_return()
+  defp run_timeline(timeline_id, ctx, state) do
+    entry = Map.fetch!(state.timelines, timeline_id)
+    if entry.timer != nil, do: Process.cancel_timer(entry.timer)
+    offline? = state.composing_strategy == :offline_processing
+
+    if state.composing_started_at == nil and not offline? do
+      {[], state}
+    else
+      now = composing_time(state)
+
+      {actions, cues, state} =
+        entry.cues
+        |> Enum.sort_by(fn {_cue_id, %{cue: cue}} -> cue.at end)
+        |> Enum.reduce({[], entry.cues, state}, fn {cue_id, cue_entry}, {actions, cues, state} ->
+          {new_actions, cue_entry, state} =
+            run_cue(timeline_id, cue_id, cue_entry, now, ctx, state)
+
+          {actions ++ new_actions, Map.put(cues, cue_id, cue_entry), state}
+        end)
+
+      deadlines =
+        Enum.flat_map(cues, fn
+          {_cue_id, %{status: :pending, cue: cue}} -> [cue.at - @timeline_lookahead]
+          {_cue_id, %{status: :scheduled, cue: cue}} when not offline? -> [cue.at]
+          _other -> []
+        end)
+
+      timer =
+        case deadlines do
+          [] ->
+            nil
+
+          deadlines ->
+            delay = max(Membrane.Time.as_milliseconds(Enum.min(deadlines) - now, :round), 0)
+            Process.send_after(self(), {:timeline_tick, timeline_id}, delay)
+        end
+
+      entry = %{entry | cues: cues, timer: timer}
+      {actions, %State{state | timelines: Map.put(state.timelines, timeline_id, entry)}}
+    end
+  end
+
+  defp run_cue(timeline_id, cue_id, cue_entry = %{status: :pending, cue: cue}, now, ctx, state) do
+    offline? = state.composing_strategy == :offline_processing
+
+    if offline? or cue.at - @timeline_lookahead <= now do
+      schedule_time = if offline? or cue.at > now, do: cue.at, else: nil
+      request = %{cue.request | schedule_time: schedule_time, ref: make_ref()}
+
+      case apply_request(request, ctx, state) do
+        {{:ok, _body}, state} ->
+          run_cue(timeline_id, cue_id, %{cue_entry | status: :scheduled}, now, ctx, state)
+
+        {{:error, reason}, state} ->
+          {[notify_parent: {:timeline_cue_failed, timeline_id, cue_id, reason}],
+           %{cue_entry | status: {:failed, reason}}, state}
+      end
+    else
+      {[], cue_entry, state}
+    end
+  end
+
+  defp run_cue(timeline_id, cue_id, cue_entry = %{status: :scheduled, cue: cue}, now, _ctx, state)
+       when state.composing_strategy != :offline_processing and cue.at <= now do
+    {[notify_parent: {:timeline_cue_fired, timeline_id, cue_id}], %{cue_entry | status: :fired},
+     promote_pending_updates(state)}
+  end
+
+  defp run_cue(_timeline_id, _cue_id, cue_entry, _now, _ctx, state), do: {[], cue_entry, state}
+
+  @spec cue_statuses(%{Timeline.cue_id() => map()}) :: %{
+          Timeline.cue_id() => Timeline.cue_status()
+        }
+  defp cue_statuses(cues) do
+    Map.new(cues, fn {cue_id, %{status: status}} -> {cue_id, status} end)
+  end
+
+  @spec track_renderers(
+          Request.t(),
+          ApiClient.request_result(),
+          Membrane.ResourceGuard.t(),
+          State.t()
+        ) :: State.t()
+  defp track_renderers(req = %module{}, {:ok, _response}, resource_guard, state)
+       when module in @register_renderer_requests do
+    renderer = renderer_ref(req)
+
+    # Server started by the bin is killed on termination, so renderers need
+    # to be cleaned up only on a server that is shared with other users.
+    if state.server_pid == nil do
+      %State{lc_address: lc_address, request_policy: policy} = state
+
+      Membrane.ResourceGuard.register(
+        resource_guard,
+        fn -> ensure_unregistered(renderer, lc_address, policy) end,
+        tag: renderer
+      )
+    end
+
+    %State{
+      state
+      | context: Context.add_renderer(renderer, state.context),
+        registrations: Map.put(state.registrations, renderer, req)
+    }
+  end
+
+  defp track_renderers(req = %module{}, {:ok, _response}, resource_guard, state)
+       when module in @unregister_renderer_requests do
+    renderer = renderer_ref(req)
+    Membrane.ResourceGuard.unregister(resource_guard, renderer)
+
+    with {:image, image_id} <- renderer,
+         image_server when image_server != nil <- state.image_server do
+      :ok = ImageServer.delete(image_server, image_id)
+    end
+
+    %State{
+      state
+      | context: Context.remove_renderer(renderer, state.context),
+        registrations: Map.delete(state.registrations, renderer)
+    }
+  end
+
+  defp track_renderers(%Request.RegisterImage{image_id: id, data: data}, _error, _guard, state)
+       when data != nil do
+    :ok = ImageServer.delete(state.image_server, id)
+    state
+  end
+
+  defp track_renderers(_req, _response, _resource_guard, state), do: state
+
+  @spec track_mp4_inputs(
+          Request.t(),
+          ApiClient.request_result(),
+          Membrane.ResourceGuard.t(),
+          State.t()
+        ) :: State.t()
+  defp track_mp4_inputs(
+         req = %Request.RegisterMp4Input{input_id: input_id},
+         {:ok, _response},
+         resource_guard,
+         state
+       ) do
+    # Inputs registered on a server started by the bin are removed together with the server.
+    if state.server_pid == nil do
+      %State{lc_address: lc_address, request_policy: policy} = state
+
+      Membrane.ResourceGuard.register(
+        resource_guard,
+        fn -> ensure_unregistered({:mp4_input, input_id}, lc_address, policy) end,
+        tag: {:mp4_input, input_id}
+      )
+    end
+
+    %State{
+      state
+      | context: Context.add_mp4_input(input_id, state.context),
+        registrations: Map.put(state.registrations, {:mp4_input, input_id}, req)
+    }
+  end
+
+  defp track_mp4_inputs(
+         %Request.UnregisterInput{input_id: input_id},
+         {:ok, _response},
+         resource_guard,
+         state
+       ) do
+    if input_id in state.context.mp4_inputs do
+      Membrane.ResourceGuard.unregister(resource_guard, {:mp4_input, input_id})
+
+      %State{
+        state
+        | context: Context.remove_input(input_id, state.context),
+          registrations: Map.delete(state.registrations, {:mp4_input, input_id})
+      }
+    else
+      state
+    end
+  end
+
+  defp track_mp4_inputs(_req, _response, _resource_guard, state), do: state
+
+  # MP4 inputs have no pads, so lifecycle notifications use pad refs of the media types of
+  # the tracks, the same as the events received from the server for that input.
+  @spec mp4_input_notifications(
+          Request.t(),
+          ApiClient.request_result() | {:error, any()},
+          State.t()
+        ) :: [Membrane.Bin.Action.t()]
+  defp mp4_input_notifications(
+         req = %Request.RegisterMp4Input{input_id: input_id},
+         {:ok, response},
+         state
+       ) do
+    for track <- mp4_tracks(req, response) do
+      pad_ref =
+        case track do
+          :video -> Pad.ref(:video_input, input_id)
+          :audio -> Pad.ref(:audio_input, input_id)
+        end
+
+      {:notify_parent, {:input_registered, pad_ref, state.context}}
+    end
+  end
+
+  defp mp4_input_notifications(_req, _response, _state), do: []
+
+  # Server reports durations of the tracks found in the file. If the response doesn't contain
+  # them, tracks declared in the request are used.
+  @spec mp4_tracks(Request.RegisterMp4Input.t(), any()) :: [:video | :audio]
+  defp mp4_tracks(_req, %{"video_duration_ms" => video, "audio_duration_ms" => audio}) do
+    for {track, duration} <- [video: video, audio: audio], duration != nil, do: track
+  end
+
+  defp mp4_tracks(req, _response), do: req.tracks
+
+  @spec maybe_serve_image_data(Request.t(), Membrane.Bin.CallbackContext.t(), State.t()) ::
+          {Request.t(), State.t()}
+  defp maybe_serve_image_data(req = %Request.RegisterImage{data: data}, ctx, state)
+       when data != nil do
+    state =
+      case state.image_server do
+        nil ->
+          {:ok, image_server} =
+            Membrane.UtilitySupervisor.start_link_child(
+              ctx.utility_supervisor,
+              {ImageServer, state.lc_address}
+            )
+
+          %State{state | image_server: image_server}
+
+        _image_server ->
+          state
+      end
+
+    url = ImageServer.put(state.image_server, req.image_id, data)
+    {%Request.RegisterImage{req | url: url, data: nil}, state}
+  end
+
+  defp maybe_serve_image_data(req, _ctx, state), do: {req, state}
+
+  @spec renderer_ref(Request.t()) :: Context.renderer()
+  defp renderer_ref(%Request.RegisterShader{shader_id: id}), do: {:shader, id}
+  defp renderer_ref(%Request.UnregisterShader{shader_id: id}), do: {:shader, id}
+  defp renderer_ref(%Request.RegisterImage{image_id: id}), do: {:image, id}
+  defp renderer_ref(%Request.UnregisterImage{image_id: id}), do: {:image, id}
+  defp renderer_ref(%Request.RegisterWebRenderer{instance_id: id}), do: {:web_renderer, id}
+  defp renderer_ref(%Request.UnregisterWebRenderer{instance_id: id}), do: {:web_renderer, id}
+
+  @spec ensure_unregistered(
+          Context.renderer() | {:mp4_input, input_id()},
+          {:inet.ip_address(), :inet.port_number()},
+          RequestPolicy.t()
+        ) :: :ok
+  defp ensure_unregistered(resource, lc_address, policy) do
+    request =
+      case resource do
+        {:shader, id} -> %Request.UnregisterShader{shader_id: id}
+        {:image, id} -> %Request.UnregisterImage{image_id: id}
+        {:web_renderer, id} -> %Request.UnregisterWebRenderer{instance_id: id}
+        {:mp4_input, id} -> %Request.UnregisterInput{input_id: id}
+      end
+
+    response =
+      request
+      |> IntoRequest.into_request()
+      |> ApiClient.send_request(lc_address,
+        policy: policy,
+        retry?: RequestPolicy.retry?(policy, request)
+      )
+
+    case response do
+      {:ok, _response} ->
+        :ok
+
+      {:error, error} ->
+        Membrane.Logger.warning(
+          "LiveCompositor failed to unregister #{inspect(resource)}. #{inspect(error)}"
+        )
+    end
+  end
+
+  @spec handle_request(Request.t(), State.t()) :: ApiClient.request_result()
+  defp handle_request(req, state) do
     response =
       IntoRequest.into_request(req)
-      |> ApiClient.send_request(lc_address)
+      |> ApiClient.send_request(state.lc_address, api_opts(req, state))
 
     case response do
-      {:error, exception} ->
+      {:error, error} ->
         Membrane.Logger.error(
-          "LiveCompositor failed to send a request: #{inspect(req)}.\nException: #{inspect(exception)}."
+          "LiveCompositor failed to send a request: #{inspect(req)}.\nError: #{inspect(error)}."
         )
 
       {:ok, _result} ->
@@ -758,4 +2271,304 @@ defmodule Membrane.LiveCompositor do
 
     response
   end
+
+  @spec api_opts(Request.t() | :internal, State.t()) :: [ApiClient.send_opt()]
+  defp api_opts(req, state) do
+    [
+      policy: state.request_policy,
+      retry?: RequestPolicy.retry?(state.request_policy, req),
+      base_url: state.api_base_url
+    ]
+  end
+
+  @spec init_request_actions(
+          Request.t(),
+          ApiClient.request_result(),
+          :raise | :notify | :ignore
+        ) :: [Membrane.Bin.Action.t()]
+  defp init_request_actions(_request, {:ok, _response}, _failure_mode), do: []
+
+  defp init_request_actions(request, {:error, error}, :raise) do
+    raise "LiveCompositor failed to handle init request: #{inspect(request)}. #{inspect(error)}"
+  end
+
+  defp init_request_actions(request, response = {:error, error}, failure_mode) do
+    Membrane.Logger.warning(
+      "LiveCompositor failed to handle init request: #{inspect(request)}. #{inspect(error)}"
+    )
+
+    case failure_mode do
+      :notify -> [notify_parent: {:request_result, request, response}]
+      :ignore -> []
+    end
+  end
+
+  # Starts a new server, see `on_server_crash` option. Starting the server can take a few
+  # seconds, so it's done in a task linked with the bin, that sends
+  # `{:live_compositor_server_restarted, ...}` once the server is ready. Until then requests
+  # are queued and timelines are stopped.
+  @spec restart_server(Membrane.Time.t(), Membrane.Bin.CallbackContext.t(), State.t()) :: :ok
+  defp restart_server(down_at, ctx, state) do
+    Membrane.ResourceGuard.unregister(ctx.resource_guard, :live_compositor_server)
+
+    bin = self()
+    options = state.options
+    utility_supervisor = ctx.utility_supervisor
+
+    {:ok, _task} =
+      Membrane.UtilitySupervisor.start_link_child(
+        utility_supervisor,
+        {Task,
+         fn ->
+           {:ok, lc_address, server_pid} =
+             ServerRunner.ensure_server_started(options, utility_supervisor)
+
+           send(bin, {:live_compositor_server_restarted, down_at, lc_address, server_pid})
+         end}
+      )
+
+    :ok
+  end
+
+  # Restores the session of the bin on the server started by `restart_server/3`.
+  @spec restore_session(
+          Membrane.Time.t(),
+          {:inet.ip_address(), :inet.port_number()},
+          pid() | nil,
+          Membrane.Bin.CallbackContext.t(),
+          State.t()
+        ) :: {[Membrane.Bin.Action.t()], State.t()}
+  defp restore_session(down_at, lc_address, server_pid, ctx, state) do
+    guard_server(server_pid, ctx)
+
+    # Updates that didn't take effect were lost with the server. Pending and scheduled cues
+    # are sent again by the timelines.
+    state = %State{promote_pending_updates(state) | pending_updates: %{}}
+
+    composing_started? = state.composing_started_at != nil
+
+    # Renderers and MP4 inputs are registered again below.
+    context = %Context{state.context | shaders: [], images: [], web_renderers: [], mp4_inputs: []}
+
+    state = %State{
+      state
+      | lc_address: lc_address,
+        server_pid: server_pid,
+        api_base_url: ApiClient.base_url(lc_address),
+        server_down?: false,
+        server_generation: state.server_generation + 1,
+        context: context,
+        timelines: rebase_timelines(state.timelines, composing_time(state)),
+        composing_started_at: nil
+    }
+
+    {init_actions, state} = send_init_requests(ctx, state)
+    {registration_actions, state} = restore_registrations(ctx, state)
+    {pad_actions, state} = restore_pads(ctx, state)
+    state = start_server_watchers(ctx, state)
+
+    state =
+      if composing_started? do
+        {:ok, _response} =
+          ApiClient.start_composing(state.lc_address, api_opts(:internal, state))
+
+        composing_started(state)
+      else
+        state
+      end
+
+    Membrane.Logger.info("LiveCompositor server was restarted and the session was restored.")
+
+    {queued_actions, state} = handle_queued_messages(ctx, state)
+
+    {init_actions ++
+       registration_actions ++
+       pad_actions ++
+       [notify_parent: {:server_restarted, down_at, Membrane.Time.os_time()}] ++
+       queued_actions, state}
+  end
+
+  # Cues that were not fired yet were lost together with the server, so they are sent again.
+  # Time of the new server is counted from its start, so the cues are shifted by the time
+  # the previous server was composing.
+  @spec rebase_timelines(map(), Membrane.Time.t()) :: map()
+  defp rebase_timelines(timelines, elapsed) do
+    Map.new(timelines, fn {timeline_id, entry} ->
+      if entry.timer != nil, do: Process.cancel_timer(entry.timer)
+
+      cues =
+        Map.new(entry.cues, fn
+          {cue_id, cue_entry = %{status: status, cue: cue}}
+          when status in [:pending, :scheduled] ->
+            cue = %Timeline.Cue{cue | at: cue.at - elapsed}
+            {cue_id, %{cue_entry | status: :pending, cue: cue}}
+
+          {cue_id, cue_entry} ->
+            {cue_id, cue_entry}
+        end)
+
+      {timeline_id, %{entry | cues: cues, timer: nil}}
+    end)
+  end
+
+  # Registers renderers and MP4 inputs that were registered with requests and were not
+  # registered again by `init_requests`.
+  @spec restore_registrations(Membrane.Bin.CallbackContext.t(), State.t()) ::
+          {[Membrane.Bin.Action.t()], State.t()}
+  defp restore_registrations(ctx, state) do
+    state.registrations
+    |> Enum.reject(fn
+      {{:mp4_input, input_id}, _request} -> input_id in state.context.mp4_inputs
+      {renderer, _request} -> Context.renderer_registered?(renderer, state.context)
+    end)
+    |> Enum.flat_map_reduce(state, fn {_resource, request}, state ->
+      {response, state} = apply_request(request, ctx, state)
+
+      actions =
+        case response do
+          {:ok, _response} -> mp4_input_notifications(request, response, state)
+          {:error, _error} -> [notify_parent: {:request_result, request, response}]
+        end
+
+      {actions, state}
+    end)
+  end
+
+  @spec restore_pads(Membrane.Bin.CallbackContext.t(), State.t()) ::
+          {[Membrane.Bin.Action.t()], State.t()}
+  defp restore_pads(ctx, state) do
+    # Inputs are registered first, so they can be used in the scenes of the outputs.
+    {inputs, outputs} =
+      ctx.pads
+      |> Enum.filter(fn {pad_ref, _pad_data} -> Map.has_key?(state.pad_ports, pad_ref) end)
+      |> Enum.split_with(fn {Pad.ref(pad_type, _pad_id), _pad_data} ->
+        pad_type in [:video_input, :audio_input]
+      end)
+
+    Enum.flat_map_reduce(inputs ++ outputs, state, fn {pad_ref, pad_data}, state ->
+      previous_port = Map.fetch!(state.pad_ports, pad_ref)
+
+      {{:ok, port}, state} =
+        register_restored_pad(pad_ref, pad_data.options, previous_port, state)
+
+      state = %State{state | pad_ports: Map.put(state.pad_ports, pad_ref, port)}
+
+      {reconnect_actions(pad_ref, pad_data.options.transport, port, state), state}
+    end)
+  end
+
+  # UDP sinks of the inputs stay bound to the ports used so far, so the input streams are
+  # registered on the same ports. Output streams are received by new elements (see
+  # `reconnect_actions/4`), so any port from the range can be used.
+  @spec register_restored_pad(Pad.ref(), map(), :inet.port_number(), State.t()) ::
+          {{:ok, :inet.port_number()} | {:error, any()}, State.t()}
+  defp register_restored_pad(Pad.ref(:video_input, pad_id), pad_options, previous_port, state) do
+    pad_options = restored_port_options(pad_options, previous_port)
+    {StreamsHandler.register_video_input_stream(pad_id, pad_options, state), state}
+  end
+
+  defp register_restored_pad(Pad.ref(:audio_input, pad_id), pad_options, previous_port, state) do
+    pad_options = restored_port_options(pad_options, previous_port)
+    {StreamsHandler.register_audio_input_stream(pad_id, pad_options, state), state}
+  end
+
+  defp register_restored_pad(
+         output_ref = Pad.ref(:video_output, pad_id),
+         pad_options,
+         _previous_port,
+         state
+       ) do
+    root = Map.get(state.video_scenes, pad_id, pad_options.initial.root)
+    pad_options = %{pad_options | initial: %{pad_options.initial | root: root}}
+    state = close_udp_socket(output_ref, state)
+    {udp_port, state} = open_udp_socket(output_ref, pad_options, state)
+
+    {StreamsHandler.register_video_output_stream(pad_id, pad_options, state, udp_port), state}
+  end
+
+  defp register_restored_pad(
+         output_ref = Pad.ref(:audio_output, pad_id),
+         pad_options,
+         _previous_port,
+         state
+       ) do
+    inputs = Map.get(state.audio_mixes, pad_id, pad_options.initial.inputs)
+    pad_options = %{pad_options | initial: %{pad_options.initial | inputs: inputs}}
+    state = close_udp_socket(output_ref, state)
+    {udp_port, state} = open_udp_socket(output_ref, pad_options, state)
+
+    {StreamsHandler.register_audio_output_stream(pad_id, pad_options, state, udp_port), state}
+  end
+
+  @spec restored_port_options(map(), :inet.port_number()) :: map()
+  defp restored_port_options(pad_options = %{transport: :udp}, previous_port),
+    do: %{pad_options | port: previous_port}
+
+  defp restored_port_options(pad_options, _previous_port), do: pad_options
+
+  # Over UDP the inputs keep sending on the same ports. Sources of the outputs and TCP elements
+  # are replaced, and the new ones are linked to the stitcher on the pad of the new generation.
+  # Video inputs are requested to send a keyframe, as the new server can't decode the stream
+  # without it. Over TCP it's requested once the connection is established.
+  @spec reconnect_actions(Pad.ref(), transport(), :inet.port_number(), State.t()) ::
+          [Membrane.Bin.Action.t()]
+  defp reconnect_actions(input_ref = Pad.ref(input_type, _pad_id), :udp, _port, state)
+       when input_type in [:video_input, :audio_input] do
+    registered_notification(input_ref, :udp, state) ++ restored_input_keyframe_request(input_ref)
+  end
+
+  defp reconnect_actions(output_ref = Pad.ref(_output_type, pad_id), :udp, port, state) do
+    previous_generation = state.server_generation - 1
+
+    links =
+      output_transport(output_ref, :udp, port, state)
+      |> via_in(Pad.ref(:input, state.server_generation))
+      |> get_child({:rtp_stitcher, pad_id})
+
+    [
+      remove_children: {:udp_source, output_ref, previous_generation},
+      spec: {links, group: output_group_id(pad_id)}
+    ] ++ registered_notification(output_ref, :udp, state)
+  end
+
+  defp reconnect_actions(input_ref = Pad.ref(input_type, pad_id), :tcp, port, state)
+       when input_type in [:video_input, :audio_input] do
+    previous_generation = state.server_generation - 1
+
+    links =
+      get_child({:bye_sender, pad_id})
+      |> via_out(Pad.ref(:output, state.server_generation))
+      |> input_transport(input_ref, :tcp, port, state)
+
+    [
+      remove_children: [
+        {:tcp_encapsulator, pad_id, previous_generation},
+        {:tcp_sink, input_ref, previous_generation}
+      ],
+      spec: {links, group: input_group_id(pad_id)}
+    ]
+  end
+
+  defp reconnect_actions(output_ref = Pad.ref(_output_type, pad_id), :tcp, port, state) do
+    previous_generation = state.server_generation - 1
+
+    links =
+      output_transport(output_ref, :tcp, port, state)
+      |> via_in(Pad.ref(:input, state.server_generation))
+      |> get_child({:rtp_stitcher, pad_id})
+
+    [
+      remove_children: [
+        {:tcp_source, output_ref, previous_generation},
+        {:tcp_decapsulator, pad_id, previous_generation}
+      ],
+      spec: {links, group: output_group_id(pad_id)}
+    ]
+  end
+
+  @spec restored_input_keyframe_request(Pad.ref()) :: [Membrane.Bin.Action.t()]
+  defp restored_input_keyframe_request(Pad.ref(:video_input, pad_id)),
+    do: [notify_child: {{:video_input_processor, pad_id}, :keyframe_request}]
+
+  defp restored_input_keyframe_request(_input_ref), do: []
 end
@@ -1,33 +1,69 @@
 defmodule Membrane.LiveCompositor.VideoOutputProcessor do
   @moduledoc false
   # Forwards buffers and send specified output stream format.
+  #
+  # H264 stream format is sent by the parser when SPS is received, so it already contains
+  # profile and real resolution of the stream. Only framerate, that is not known to
+  # the parser, is taken from `output_stream_format`. VP8 depayloader sends `RemoteStream`,
+  # so it's replaced with `output_stream_format`.
+  #
+  # With `notify_pts?` the parent is notified with `{:pts, pts}` about every buffer, so it can
+  # track progress of the offline processing.
 
   use Membrane.Filter
 
+  alias Membrane.RemoteStream
+
   def_options output_stream_format: [
-                spec: Membrane.H264.t()
+                spec: Membrane.H264.t() | Membrane.VP8.t() | Membrane.RawVideo.t()
+              ],
+              notify_pts?: [
+                spec: boolean(),
+                default: false
               ]
 
   def_input_pad :input,
-    accepted_format: %Membrane.H264{alignment: :nalu, stream_structure: :annexb},
+    accepted_format:
+      any_of(
+        %Membrane.H264{alignment: :nalu, stream_structure: :annexb},
+        Membrane.VP8,
+        %RemoteStream{content_format: Membrane.VP8},
+        %Membrane.RawVideo{pixel_format: :I420}
+      ),
     availability: :on_request,
     flow_control: :auto
 
   def_output_pad :output,
-    accepted_format: %Membrane.H264{alignment: :nalu, stream_structure: :annexb},
+    accepted_format:
+      any_of(
+        %Membrane.H264{alignment: :nalu, stream_structure: :annexb},
+        Membrane.VP8,
+        %Membrane.RawVideo{pixel_format: :I420}
+      ),
     flow_control: :auto
 
   @impl true
   def handle_init(_ctx, opt) do
     {[],
      %{
-       output_stream_format: opt.output_stream_format
+       output_stream_format: opt.output_stream_format,
+       notify_pts?: opt.notify_pts?
      }}
   end
 
   @impl true
   def handle_buffer(_pad, buffer, _ctx, state) do
-    {[buffer: {:output, buffer}], state}
+    {pts_notification(buffer, state) ++ [buffer: {:output, buffer}], state}
+  end
+
+  @impl true
+  def handle_stream_format(
+        _pad,
+        stream_format = %Membrane.H264{},
+        _ctx,
+        state = %{output_stream_format: %Membrane.H264{framerate: framerate}}
+      ) do
+    {[stream_format: {:output, %Membrane.H264{stream_format | framerate: framerate}}], state}
   end
 
   @impl true
@@ -49,13 +85,30 @@ defmodule Membrane.LiveCompositor.VideoOutputProcessor do
   def handle_event(:output, event, _ctx, state) do
     {[event: {:input, event}], state}
   end
+
+  @spec pts_notification(Membrane.Buffer.t(), map()) :: [Membrane.Element.Action.t()]
+  def pts_notification(%Membrane.Buffer{pts: pts}, %{notify_pts?: true}) when pts != nil,
+    do: [notify_parent: {:pts, pts}]
+
+  def pts_notification(_buffer, _state), do: []
 end
 
 defmodule Membrane.LiveCompositor.AudioOutputProcessor do
   @moduledoc false
+  # Forwards buffers and sends Opus stream format with the channel count of the encoder.
+  # `notify_pts?` works the same as in `Membrane.LiveCompositor.VideoOutputProcessor`.
 
   use Membrane.Filter
   alias Membrane.{Opus, RemoteStream}
+  alias Membrane.LiveCompositor.VideoOutputProcessor
+
+  def_options channels: [
+                spec: 1 | 2
+              ],
+              notify_pts?: [
+                spec: boolean(),
+                default: false
+              ]
 
   def_input_pad :input,
     accepted_format: %RemoteStream{type: :packetized, content_format: Opus},
@@ -63,21 +116,22 @@ defmodule Membrane.LiveCompositor.AudioOutputProcessor do
     flow_control: :auto
 
   def_output_pad :output,
-    accepted_format: %RemoteStream{type: :packetized, content_format: Opus},
+    accepted_format: %Opus{self_delimiting?: false},
     flow_control: :auto
 
   @impl true
-  def handle_init(_ctx, _opt) do
-    {[], %{}}
+  def handle_init(_ctx, opt) do
+    {[], %{channels: opt.channels, notify_pts?: opt.notify_pts?}}
   end
 
   @impl true
   def handle_buffer(_pad, buffer, _ctx, state) do
-    {[buffer: {:output, buffer}], state}
+    {VideoOutputProcessor.pts_notification(buffer, state) ++ [buffer: {:output, buffer}], state}
   end
 
   @impl true
-  def handle_stream_format(_pad, stream_format, _ctx, state) do
+  def handle_stream_format(_pad, _stream_format, _ctx, state) do
+    stream_format = %Opus{channels: state.channels, self_delimiting?: false}
     {[stream_format: {:output, stream_format}], state}
   end
 end
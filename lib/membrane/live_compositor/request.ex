@@ -4,10 +4,19 @@ defmodule Membrane.LiveCompositor.Request do
 
   After request is handled (but not necessarily completed e.g. if you schedule some action) the bin will
   respond with `t:result/0`.
+
+  Every request has an optional `:ref` field that can be used to match the request with its result.
+  If `:ref` is not defined, the bin generates a new reference with `make_ref/0` and puts it in the
+  request returned in `t:result/0`.
   """
 
   alias Membrane.LiveCompositor.ApiClient
 
+  @typedoc """
+  Reference used to correlate a request with its result. Any term can be used.
+  """
+  @type ref :: term()
+
   defmodule RegisterImage do
     @moduledoc """
     Request to register an image.
@@ -19,10 +28,14 @@ defmodule Membrane.LiveCompositor.Request do
     @type image_type :: :png | :jpeg | :gif | :svg
 
     @enforce_keys [:image_id, :asset_type]
-    defstruct @enforce_keys ++ [url: nil, path: nil, resolution: nil]
+    defstruct @enforce_keys ++ [url: nil, path: nil, data: nil, resolution: nil, ref: nil]
 
     @typedoc """
-    - Fields `url` and `path` are mutually exclusive. Exactly one of them should be set at the time.
+    - Fields `url`, `path` and `data` are mutually exclusive. Exactly one of them should be set
+    at the time.
+    - Field `data` contains content of the image file. The bin serves it to the LiveCompositor
+    server over HTTP, so it can be used even if the server runs on a different host. Data is kept
+    by the bin until the image is unregistered.
     - Field `resolution` is only supported when `:asset_type` is set to `:svg`.
     """
     @type t :: %__MODULE__{
@@ -30,7 +43,9 @@ defmodule Membrane.LiveCompositor.Request do
             asset_type: image_type(),
             url: String.t() | nil,
             path: String.t() | nil,
-            resolution: %{width: non_neg_integer(), height: non_neg_integer()} | nil
+            data: binary() | nil,
+            resolution: %{width: non_neg_integer(), height: non_neg_integer()} | nil,
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -62,7 +77,7 @@ defmodule Membrane.LiveCompositor.Request do
     """
 
     @enforce_keys [:shader_id, :source]
-    defstruct @enforce_keys ++ []
+    defstruct @enforce_keys ++ [ref: nil]
 
     @typedoc """
     WGSL shader source code. [Learn more](https://compositor.live/docs/concept/shaders).
@@ -71,7 +86,8 @@ defmodule Membrane.LiveCompositor.Request do
 
     @type t :: %__MODULE__{
             shader_id: String.t(),
-            source: shader_source()
+            source: shader_source(),
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -87,16 +103,136 @@ defmodule Membrane.LiveCompositor.Request do
     end
   end
 
+  defmodule RegisterWebRenderer do
+    @moduledoc """
+    Request to register a web renderer instance. Registered instance can be used in the scene with
+    `Membrane.LiveCompositor.Scene.WebView` component.
+
+    Web rendering needs to be enabled with `web_renderer_enabled?: true` option of the bin, and
+    the LiveCompositor server needs to be built with web rendering support.
+    """
+
+    @typedoc """
+    Defines how `Membrane.LiveCompositor.Scene.WebView` children are embedded into the website.
+    [Learn more](https://compositor.live/docs/api/renderers/web)
+    """
+    @type embedding_method ::
+            :chromium_embedding
+            | :native_embedding_over_content
+            | :native_embedding_under_content
+
+    @enforce_keys [:instance_id, :url, :resolution]
+    defstruct @enforce_keys ++ [embedding_method: nil, ref: nil]
+
+    @typedoc """
+    - `:instance_id` - Id of the web renderer instance.
+    - `:url` - URL of the website that should be rendered.
+    - `:resolution` - Resolution of the rendered website.
+    - `:embedding_method` - Defaults to `:chromium_embedding`.
+    """
+    @type t :: %__MODULE__{
+            instance_id: String.t(),
+            url: String.t(),
+            resolution: %{width: non_neg_integer(), height: non_neg_integer()},
+            embedding_method: embedding_method() | nil,
+            ref: Membrane.LiveCompositor.Request.ref()
+          }
+  end
+
+  defimpl ApiClient.IntoRequest, for: RegisterWebRenderer do
+    @spec into_request(RegisterWebRenderer.t()) :: ApiClient.request()
+    def into_request(request) do
+      body = %{
+        url: request.url,
+        resolution: request.resolution,
+        embedding_method: request.embedding_method
+      }
+
+      encoded_id = URI.encode_www_form(request.instance_id)
+      {:post, "/api/web-renderer/#{encoded_id}/register", body}
+    end
+  end
+
+  defmodule RegisterMp4Input do
+    @moduledoc """
+    Request to register an input stream that is read from an MP4 file by the LiveCompositor
+    server itself, without any Membrane elements or pads involved.
+
+    The registered input is added to `Membrane.LiveCompositor.Context` and it can be used in
+    scenes and audio updates the same way as inputs registered with pads. See
+    `Membrane.LiveCompositor.Lifecycle` for its lifecycle notifications. The input can be
+    unregistered with `Membrane.LiveCompositor.Request.UnregisterInput`.
+    """
+
+    alias Membrane.LiveCompositor
+
+    @enforce_keys [:input_id]
+    defstruct @enforce_keys ++
+                [
+                  url: nil,
+                  path: nil,
+                  loop: false,
+                  offset: nil,
+                  required: false,
+                  tracks: [:video, :audio],
+                  ref: nil
+                ]
+
+    @typedoc """
+    - `:input_id` - Id of the input, it needs to be unique among all inputs of the bin.
+    - Fields `:url` and `:path` are mutually exclusive. Exactly one of them should be set at the
+    time. `:path` is resolved on the host where the LiveCompositor server runs.
+    - `:loop` - If `true`, the file is played in a loop. Defaults to `false`.
+    - `:offset` - The same as `:offset` option of the `:video_input` pad.
+    - `:required` - The same as `:required` option of the `:video_input` pad. Defaults to `false`.
+    - `:tracks` - Tracks of the file, used for
+    `t:Membrane.LiveCompositor.Lifecycle.input_registered/0` notifications only if the server
+    doesn't report the tracks it found in the file. Defaults to `[:video, :audio]`.
+    """
+    @type t :: %__MODULE__{
+            input_id: LiveCompositor.input_id(),
+            url: String.t() | nil,
+            path: String.t() | nil,
+            loop: boolean(),
+            offset: Membrane.Time.t() | nil,
+            required: boolean(),
+            tracks: [:video | :audio],
+            ref: Membrane.LiveCompositor.Request.ref()
+          }
+  end
+
+  defimpl ApiClient.IntoRequest, for: RegisterMp4Input do
+    @spec into_request(RegisterMp4Input.t()) :: ApiClient.request()
+    def into_request(request) do
+      body = %{
+        type: :mp4,
+        url: request.url,
+        path: request.path,
+        loop: request.loop,
+        required: request.required,
+        offset_ms:
+          case request.offset do
+            nil -> nil
+            offset -> Membrane.Time.as_milliseconds(offset, :round)
+          end
+      }
+
+      encoded_id = URI.encode_www_form(request.input_id)
+      {:post, "/api/input/#{encoded_id}/register", body}
+    end
+  end
+
   defmodule UnregisterImage do
     @moduledoc """
     Request to unregister an image.
     """
 
     @enforce_keys [:image_id]
-    defstruct @enforce_keys ++ []
+    defstruct @enforce_keys ++ [ref: nil]
 
     @type t :: %__MODULE__{
-            image_id: String.t()
+            image_id: String.t(),
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -114,10 +250,11 @@ defmodule Membrane.LiveCompositor.Request do
     """
 
     @enforce_keys [:shader_id]
-    defstruct @enforce_keys ++ []
+    defstruct @enforce_keys ++ [ref: nil]
 
     @type t :: %__MODULE__{
-            shader_id: String.t()
+            shader_id: String.t(),
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -129,6 +266,28 @@ defmodule Membrane.LiveCompositor.Request do
     end
   end
 
+  defmodule UnregisterWebRenderer do
+    @moduledoc """
+    Request to unregister a web renderer instance.
+    """
+
+    @enforce_keys [:instance_id]
+    defstruct @enforce_keys ++ [ref: nil]
+
+    @type t :: %__MODULE__{
+            instance_id: String.t(),
+            ref: Membrane.LiveCompositor.Request.ref()
+          }
+  end
+
+  defimpl ApiClient.IntoRequest, for: UnregisterWebRenderer do
+    @spec into_request(UnregisterWebRenderer.t()) :: ApiClient.request()
+    def into_request(request) do
+      encoded_id = URI.encode_www_form(request.instance_id)
+      {:post, "/api/web-renderer/#{encoded_id}/unregister", %{}}
+    end
+  end
+
   defmodule UnregisterInput do
     @moduledoc """
     Request to unregister an input stream. Unregister will happen automatically when you unlink the
@@ -138,11 +297,12 @@ defmodule Membrane.LiveCompositor.Request do
     alias Membrane.LiveCompositor
 
     @enforce_keys [:input_id]
-    defstruct @enforce_keys ++ [schedule_time: nil]
+    defstruct @enforce_keys ++ [schedule_time: nil, ref: nil]
 
     @type t :: %__MODULE__{
             input_id: LiveCompositor.input_id(),
-            schedule_time: Membrane.Time | nil
+            schedule_time: Membrane.Time | nil,
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -171,11 +331,12 @@ defmodule Membrane.LiveCompositor.Request do
     alias Membrane.LiveCompositor
 
     @enforce_keys [:output_id]
-    defstruct @enforce_keys ++ [schedule_time: nil]
+    defstruct @enforce_keys ++ [schedule_time: nil, ref: nil]
 
     @type t :: %__MODULE__{
             output_id: LiveCompositor.output_id(),
-            schedule_time: Membrane.Time | nil
+            schedule_time: Membrane.Time | nil,
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -201,20 +362,32 @@ defmodule Membrane.LiveCompositor.Request do
     """
 
     alias Membrane.LiveCompositor
+    alias Membrane.LiveCompositor.Scene
 
     @enforce_keys [:output_id, :root]
-    defstruct @enforce_keys ++ [schedule_time: nil]
+    defstruct @enforce_keys ++ [schedule_time: nil, transition: nil, ref: nil]
+
+    @typedoc """
+    Transition duration and optional easing function.
+    """
+    @type transition :: {duration :: Membrane.Time.t(), Scene.easing_function() | nil}
 
     @typedoc """
     - `:output_id` - Id of the output that should be updated.
-    - `:root` - Root of a component tree/scene that should be rendered for the output. [Learn more](https://compositor.live/docs/concept/component)
+    - `:root` - Root of a component tree/scene that should be rendered for the output. See
+    `Membrane.LiveCompositor.Scene` for available components.
     - `:schedule_time` - Schedule this update at a specific time. Time is measured from compositor
     start. If not defined, update will be applied immediately.
+    - `:transition` - If defined, the bin compares `:root` with the scene previously sent for this
+    output and adds this transition to every `View`, `Tiles` and `Rescaler` whose `id` was present
+    in the previous scene. Components that already define a `transition` are not modified.
     """
     @type t :: %__MODULE__{
             output_id: LiveCompositor.output_id(),
-            root: any(),
-            schedule_time: Membrane.Time | nil
+            root: Scene.component(),
+            schedule_time: Membrane.Time | nil,
+            transition: transition() | nil,
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -246,7 +419,7 @@ defmodule Membrane.LiveCompositor.Request do
     alias Membrane.LiveCompositor
 
     @enforce_keys [:output_id, :inputs]
-    defstruct @enforce_keys ++ [schedule_time: nil]
+    defstruct @enforce_keys ++ [schedule_time: nil, ref: nil]
 
     @typedoc """
     - `input_id` - ID of an input that will be used to produce output.
@@ -263,7 +436,8 @@ defmodule Membrane.LiveCompositor.Request do
     @type t :: %__MODULE__{
             output_id: LiveCompositor.output_id(),
             inputs: list(input()),
-            schedule_time: Membrane.Time | nil
+            schedule_time: Membrane.Time | nil,
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -292,13 +466,14 @@ defmodule Membrane.LiveCompositor.Request do
     """
 
     @enforce_keys [:output_id]
-    defstruct @enforce_keys
+    defstruct @enforce_keys ++ [ref: nil]
 
     @typedoc """
     - `:output_id` - Id of the output for which additional keyframe should be generated.
     """
     @type t :: %__MODULE__{
-            output_id: Membrane.LiveCompositor.output_id()
+            output_id: Membrane.LiveCompositor.output_id(),
+            ref: Membrane.LiveCompositor.Request.ref()
           }
   end
 
@@ -310,16 +485,153 @@ defmodule Membrane.LiveCompositor.Request do
     end
   end
 
+  defmodule UpdateOutputs do
+    @moduledoc """
+    Request to update multiple outputs at the same time.
+
+    Each `Membrane.LiveCompositor.Request.UpdateVideoOutput` or
+    `Membrane.LiveCompositor.Request.UpdateAudioOutput` is sent to the server as a separate
+    request, but all of them are scheduled at the same time, so they take effect on the same frame.
+
+    The bin responds with a single `t:Membrane.LiveCompositor.Request.result/0`. Scenes of all
+    video updates are validated before any update is sent. `:schedule_time` and `:ref` of
+    individual updates are ignored.
+
+    Updates are only aligned in time, the batch is not atomic. If the server rejects one of
+    the updates, the updates it accepted still take effect. Check the results of individual
+    updates in `{:error, {:update_outputs_failed, results}}` to find out which outputs were
+    updated.
+
+    If any update reaches the server after the schedule time, e.g. because the request was
+    retried according to `Membrane.LiveCompositor.RequestPolicy`, the bin responds with
+    `{:error, {:schedule_time_missed, results}}`, as updates may have taken effect on
+    different frames. Increase `:delay` if the server needs more time to receive all updates.
+
+    This request can only be handled by the bin, it can't be sent directly to the server.
+    """
+
+    @enforce_keys [:updates]
+    defstruct @enforce_keys ++ [schedule_time: nil, delay: nil, ref: nil]
+
+    @typedoc """
+    - `:updates` - Updates that should be applied together.
+    - `:schedule_time` - Schedule all updates at a specific time. Time is measured from
+    compositor start. The request is rejected with `{:error, {:schedule_time_passed, time}}`
+    if the time has already passed.
+    - `:delay` - Used when `:schedule_time` is not defined. All updates are scheduled `delay`
+    after the moment the bin received the request, so the server has enough time to receive all
+    of them. Defaults to 100 milliseconds. When `composing_strategy` is `:offline_processing`,
+    the delay is counted from the PTS of the last buffer received on the outputs. When the
+    composing was not started yet, updates are scheduled at its start.
+    """
+    @type t :: %__MODULE__{
+            updates: [UpdateVideoOutput.t() | UpdateAudioOutput.t()],
+            schedule_time: Membrane.Time.t() | nil,
+            delay: Membrane.Time.t() | nil,
+            ref: Membrane.LiveCompositor.Request.ref()
+          }
+  end
+
+  defmodule SubmitTimeline do
+    @moduledoc """
+    Request to submit a `Membrane.LiveCompositor.Timeline` to the bin.
+
+    If a timeline with the same id was already submitted, cues are merged into it. Pending cues
+    with the same id are replaced, cues with the same id that were already sent to the server are
+    left unchanged.
+
+    The bin responds with `{:ok, statuses}`, where `statuses` is a map from cue ids of the
+    timeline to their `t:Membrane.LiveCompositor.Timeline.cue_status/0`. If some cues contain
+    requests that are not `t:Membrane.LiveCompositor.Timeline.cue_request/0`, the whole timeline
+    is rejected with `{:error, {:invalid_cues, cue_ids}}`.
+
+    This request can only be handled by the bin, it can't be sent directly to the server.
+    """
+
+    alias Membrane.LiveCompositor.Timeline
+
+    @enforce_keys [:timeline]
+    defstruct @enforce_keys ++ [ref: nil]
+
+    @type t :: %__MODULE__{
+            timeline: Timeline.t(),
+            ref: Membrane.LiveCompositor.Request.ref()
+          }
+  end
+
+  defmodule CancelTimeline do
+    @moduledoc """
+    Request to cancel pending cues of a timeline submitted with
+    `Membrane.LiveCompositor.Request.SubmitTimeline`.
+
+    Only `:pending` cues can be cancelled. The bin responds with `{:ok, statuses}` in the same
+    format as for `Membrane.LiveCompositor.Request.SubmitTimeline`, or with
+    `{:error, {:unknown_timeline, timeline_id}}`.
+
+    This request can only be handled by the bin, it can't be sent directly to the server.
+    """
+
+    alias Membrane.LiveCompositor.Timeline
+
+    @enforce_keys [:timeline_id]
+    defstruct @enforce_keys ++ [cue_ids: nil, ref: nil]
+
+    @typedoc """
+    - `:timeline_id` - Id of the timeline.
+    - `:cue_ids` - Ids of cues that should be cancelled. If `nil`, all pending cues of the timeline
+    are cancelled.
+    """
+    @type t :: %__MODULE__{
+            timeline_id: Timeline.id(),
+            cue_ids: [Timeline.cue_id()] | nil,
+            ref: Membrane.LiveCompositor.Request.ref()
+          }
+  end
+
   @type t ::
           RegisterImage.t()
           | RegisterShader.t()
+          | RegisterWebRenderer.t()
+          | RegisterMp4Input.t()
           | UnregisterImage.t()
           | UnregisterShader.t()
+          | UnregisterWebRenderer.t()
           | UnregisterInput.t()
           | UnregisterOutput.t()
           | UpdateVideoOutput.t()
           | UpdateAudioOutput.t()
+          | UpdateOutputs.t()
+          | SubmitTimeline.t()
+          | CancelTimeline.t()
           | KeyframeRequest.t()
 
-  @type result :: {:request_result, t(), {:ok, any()} | {:error, any()}}
+  @typedoc """
+  Result of a request.
+
+  On success, it contains decoded body of the server response. If the request failed on the
+  server side or the server was not reachable, it contains `Membrane.LiveCompositor.Error`.
+
+  `Membrane.LiveCompositor.Request.UpdateVideoOutput` is validated by the bin before it is sent to
+  the server. If validation fails, the result is `{:error, {:invalid_scene, reasons}}` and the
+  request is not sent.
+
+  Result of `Membrane.LiveCompositor.Request.UpdateOutputs` is `{:ok, results}` if all updates
+  succeeded or `{:error, {:update_outputs_failed, results}}` otherwise, where `results` is a list
+  of results of individual updates.
+
+  Registering a renderer with an id that was already registered by this bin results in
+  `{:error, {:already_registered, renderer}}` without sending the request. The same applies to
+  `Membrane.LiveCompositor.Request.RegisterMp4Input` with an id of an already registered input,
+  which results in `{:error, {:already_registered, {:input, input_id}}}`.
+  """
+  @type result ::
+          {:request_result, t(),
+           {:ok, body :: any()}
+           | {:error, Membrane.LiveCompositor.Error.t()}
+           | {:error, {:invalid_scene, [Membrane.LiveCompositor.Scene.validation_error()]}}
+           | {:error, {:already_registered, Membrane.LiveCompositor.Context.renderer()}}
+           | {:error, {:already_registered, {:input, Membrane.LiveCompositor.input_id()}}}
+           | {:error, {:update_outputs_failed, [{:ok, any()} | {:error, any()}]}}
+           | {:error, {:invalid_cues, [Membrane.LiveCompositor.Timeline.cue_id()]}}
+           | {:error, {:unknown_timeline, Membrane.LiveCompositor.Timeline.id()}}}
 end
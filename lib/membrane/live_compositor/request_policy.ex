@@ -0,0 +1,79 @@
+defmodule Membrane.LiveCompositor.RequestPolicy do
+  @moduledoc """
+  Timeouts and retries of requests sent by the bin to the LiveCompositor server.
+
+  Only failures that are likely to be transient (e.g. connection errors, timeouts,
+  `408`, `429`, `5xx` responses) are retried.
+  """
+
+  alias Membrane.LiveCompositor.Request
+
+  # Requests that have the same effect when applied more than once.
+  @safe_requests [
+    Request.UpdateVideoOutput,
+    Request.UpdateAudioOutput,
+    Request.KeyframeRequest
+  ]
+
+  defstruct timeout: Membrane.Time.seconds(15),
+            max_retries: 3,
+            backoff: :linear,
+            base_delay: Membrane.Time.milliseconds(100),
+            retry: :safe
+
+  @typedoc """
+  - `:linear` - `n`-th retry is delayed by `n * base_delay`.
+  - `:exponential` - `n`-th retry is delayed by `2^(n - 1) * base_delay`.
+  - `:constant` - Every retry is delayed by `base_delay`.
+  """
+  @type backoff :: :linear | :exponential | :constant
+
+  @typedoc """
+  - `:safe` - Retry only requests that can be safely applied more than once, i.e.
+  `Membrane.LiveCompositor.Request.UpdateVideoOutput`,
+  `Membrane.LiveCompositor.Request.UpdateAudioOutput` and
+  `Membrane.LiveCompositor.Request.KeyframeRequest`.
+  - `:all` - Retry all requests, including registration of the input and output streams and
+  the request that starts composing.
+  - `:none` - Never retry.
+  - list of `Membrane.LiveCompositor.Request` modules that should be retried.
+  """
+  @type retry :: :safe | :all | :none | [module()]
+
+  @typedoc """
+  - `:timeout` - How long to wait for the response of the server. Defaults to 15 seconds.
+  - `:max_retries` - Maximal number of retries of a single request. Defaults to `3`.
+  - `:backoff` - See `t:backoff/0`. Defaults to `:linear`.
+  - `:base_delay` - Base delay used to calculate backoff. Defaults to 100 milliseconds.
+  - `:retry` - See `t:retry/0`. Defaults to `:safe`.
+  """
+  @type t :: %__MODULE__{
+          timeout: Membrane.Time.t(),
+          max_retries: non_neg_integer(),
+          backoff: backoff(),
+          base_delay: Membrane.Time.t(),
+          retry: retry()
+        }
+
+  @doc false
+  @spec retry?(t(), Request.t() | :internal) :: boolean()
+  def retry?(%__MODULE__{retry: :all}, _request), do: true
+  def retry?(%__MODULE__{retry: :none}, _request), do: false
+  def retry?(%__MODULE__{retry: :safe}, %module{}), do: module in @safe_requests
+  def retry?(%__MODULE__{retry: modules}, %module{}) when is_list(modules), do: module in modules
+  def retry?(%__MODULE__{}, :internal), do: false
+
+  @doc false
+  @spec retry_delay(t(), retry_count :: pos_integer()) :: Membrane.Time.t()
+  def retry_delay(%__MODULE__{backoff: :linear, base_delay: base_delay}, retry_count) do
+    retry_count * base_delay
+  end
+
+  def retry_delay(%__MODULE__{backoff: :exponential, base_delay: base_delay}, retry_count) do
+    Integer.pow(2, retry_count - 1) * base_delay
+  end
+
+  def retry_delay(%__MODULE__{backoff: :constant, base_delay: base_delay}, _retry_count) do
+    base_delay
+  end
+end
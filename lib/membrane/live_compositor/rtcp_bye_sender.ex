@@ -1,5 +1,8 @@
 defmodule Membrane.LiveCompositor.RtcpByeSender do
   @moduledoc false
+  # Output pad is dynamic, so the transport to the LiveCompositor server can be recreated
+  # after the server is restarted. Newly linked pads receive the current stream format
+  # (and end of stream if the stream already ended).
 
   use Membrane.Filter
 
@@ -15,21 +18,32 @@ defmodule Membrane.LiveCompositor.RtcpByeSender do
 
   def_output_pad :output,
     accepted_format: %RemoteStream{type: :packetized, content_format: RTP},
+    availability: :on_request,
     flow_control: :auto
 
   @impl true
   def handle_init(_ctx, opt) do
-    {[], %{ssrc: opt.ssrc}}
+    {[], %{ssrc: opt.ssrc, stream_format: nil, end_of_stream?: false}}
+  end
+
+  @impl true
+  def handle_pad_added(pad, _ctx, state) do
+    stream_format_actions =
+      if state.stream_format != nil, do: [stream_format: {pad, state.stream_format}], else: []
+
+    end_of_stream_actions = if state.end_of_stream?, do: [end_of_stream: pad], else: []
+
+    {stream_format_actions ++ end_of_stream_actions, state}
   end
 
   @impl true
   def handle_buffer(_pad, buffer, _ctx, state) do
-    {[buffer: {:output, buffer}], state}
+    {[forward: buffer], state}
   end
 
   @impl true
   def handle_stream_format(_pad, stream_format, _ctx, state) do
-    {[stream_format: {:output, stream_format}], state}
+    {[forward: stream_format], %{state | stream_format: stream_format}}
   end
 
   @impl true
@@ -40,8 +54,8 @@ defmodule Membrane.LiveCompositor.RtcpByeSender do
     }
 
     {[
-       buffer: {:output, %Buffer{payload: RTCP.Packet.serialize(packet)}},
-       end_of_stream: :output
-     ], state}
+       forward: %Buffer{payload: RTCP.Packet.serialize(packet)},
+       forward: :end_of_stream
+     ], %{state | end_of_stream?: true}}
   end
 end
@@ -0,0 +1,116 @@
+defmodule Membrane.LiveCompositor.RtpStreamStitcher do
+  @moduledoc false
+  # Joins RTP streams received from consecutive instances of the LiveCompositor server into
+  # a single continuous stream, so the elements after it (RTP demuxer, jitter buffer) don't
+  # notice that the server was restarted.
+  #
+  # Packets sent by a new instance have a different SSRC. Their sequence numbers and timestamps
+  # are shifted to continue the previous stream and the SSRC of the first stream is kept. RTCP
+  # packets of later instances are dropped. Over TCP every instance is received on a new
+  # input pad.
+  #
+  # End of stream on input pads is not forwarded, because the connection is also closed when
+  # the server crashes. Instead the parent is notified with `{:end_of_stream, pad}` and it
+  # sends `:end_of_stream` back if the stream really ended.
+
+  use Membrane.Filter
+
+  import Bitwise
+
+  alias Membrane.{Buffer, RemoteStream}
+
+  def_options clock_rate: [
+                spec: pos_integer()
+              ]
+
+  def_input_pad :input,
+    accepted_format: %RemoteStream{type: :packetized},
+    availability: :on_request,
+    flow_control: :auto
+
+  def_output_pad :output,
+    accepted_format: %RemoteStream{type: :packetized},
+    flow_control: :auto
+
+  @impl true
+  def handle_init(_ctx, opt) do
+    {[],
+     %{
+       clock_rate: opt.clock_rate,
+       stream_format_sent?: false,
+       ssrc: nil,
+       input_ssrc: nil,
+       seq_offset: 0,
+       timestamp_offset: 0,
+       last_seq: nil,
+       last_timestamp: nil,
+       last_packet_time: nil,
+       restarted?: false
+     }}
+  end
+
+  @impl true
+  def handle_stream_format(_pad, stream_format, _ctx, state = %{stream_format_sent?: false}) do
+    {[stream_format: {:output, stream_format}], %{state | stream_format_sent?: true}}
+  end
+
+  @impl true
+  def handle_stream_format(_pad, _stream_format, _ctx, state) do
+    {[], state}
+  end
+
+  @impl true
+  def handle_buffer(_pad, buffer, _ctx, state) do
+    case buffer.payload do
+      <<_first_byte, packet_type, _rest::binary>> when packet_type in 200..204 ->
+        if state.restarted?, do: {[], state}, else: {[buffer: {:output, buffer}], state}
+
+      <<header::binary-size(2), seq::16, timestamp::32, ssrc::32, rest::binary>> ->
+        state = maybe_start_segment(ssrc, seq, timestamp, state)
+        seq = band(seq + state.seq_offset, 0xFFFF)
+        timestamp = band(timestamp + state.timestamp_offset, 0xFFFFFFFF)
+        payload = <<header::binary, seq::16, timestamp::32, state.ssrc::32, rest::binary>>
+
+        state = %{
+          state
+          | last_seq: seq,
+            last_timestamp: timestamp,
+            last_packet_time: Membrane.Time.monotonic_time()
+        }
+
+        {[buffer: {:output, %Buffer{buffer | payload: payload}}], state}
+
+      _malformed ->
+        {[], state}
+    end
+  end
+
+  @impl true
+  def handle_end_of_stream(pad, _ctx, state) do
+    {[notify_parent: {:end_of_stream, pad}], state}
+  end
+
+  @impl true
+  def handle_parent_notification(:end_of_stream, _ctx, state) do
+    {[end_of_stream: :output], state}
+  end
+
+  defp maybe_start_segment(ssrc, _seq, _timestamp, state = %{input_ssrc: ssrc}), do: state
+
+  defp maybe_start_segment(ssrc, _seq, _timestamp, state = %{input_ssrc: nil}) do
+    %{state | ssrc: ssrc, input_ssrc: ssrc}
+  end
+
+  defp maybe_start_segment(ssrc, seq, timestamp, state) do
+    elapsed = Membrane.Time.monotonic_time() - state.last_packet_time
+    elapsed_ticks = div(elapsed * state.clock_rate, Membrane.Time.second())
+
+    %{
+      state
+      | input_ssrc: ssrc,
+        seq_offset: state.last_seq + 1 - seq,
+        timestamp_offset: state.last_timestamp + elapsed_ticks - timestamp,
+        restarted?: true
+    }
+  end
+end
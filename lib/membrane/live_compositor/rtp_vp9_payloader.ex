@@ -0,0 +1,86 @@
+defmodule Membrane.LiveCompositor.RtpVp9Payloader do
+  @moduledoc false
+  # Splits VP9 frames into RTP payloads, `membrane_rtp_vp9_plugin` provides only a depayloader.
+  #
+  # Every payload starts with a one byte VP9 payload descriptor (RFC 9628) in non-flexible mode,
+  # without picture ID and layer indices. Start and end of the frame are marked with B and E
+  # bits, P bit is cleared only on keyframes. RTP marker bit is set on the last packet of
+  # the frame.
+
+  use Membrane.Filter
+
+  alias Membrane.{Buffer, RemoteStream, RTP, VP9}
+
+  def_options max_payload_size: [
+                spec: pos_integer(),
+                default: 1200,
+                description: "Maximal size of the RTP payload, including the payload descriptor."
+              ]
+
+  def_input_pad :input,
+    accepted_format: any_of(VP9, %RemoteStream{content_format: VP9}),
+    flow_control: :auto
+
+  def_output_pad :output,
+    accepted_format: RTP,
+    flow_control: :auto
+
+  @impl true
+  def handle_init(_ctx, opt) do
+    {[], %{max_payload_size: opt.max_payload_size}}
+  end
+
+  @impl true
+  def handle_stream_format(:input, _stream_format, _ctx, state) do
+    {[stream_format: {:output, %RTP{}}], state}
+  end
+
+  @impl true
+  def handle_buffer(:input, buffer, _ctx, state) do
+    inter_picture = if keyframe?(buffer.payload), do: 0, else: 1
+    chunks = split(buffer.payload, state.max_payload_size - 1)
+    last_index = length(chunks) - 1
+
+    buffers =
+      chunks
+      |> Enum.with_index()
+      |> Enum.map(fn {chunk, index} ->
+        start_of_frame = if index == 0, do: 1, else: 0
+        end_of_frame = if index == last_index, do: 1, else: 0
+
+        descriptor =
+          <<0::1, inter_picture::1, 0::1, 0::1, start_of_frame::1, end_of_frame::1, 0::2>>
+
+        %Buffer{
+          buffer
+          | payload: descriptor <> chunk,
+            metadata: Map.put(buffer.metadata, :rtp, %{marker: index == last_index})
+        }
+      end)
+
+    {[buffer: {:output, buffers}], state}
+  end
+
+  # Uncompressed header starts with frame marker, profile, (reserved bit for profile 3),
+  # show_existing_frame flag and frame type, which is 0 for keyframes.
+  @spec keyframe?(binary()) :: boolean()
+  defp keyframe?(<<2::2, profile_low::1, profile_high::1, rest::bitstring>>) do
+    rest =
+      case {profile_high, profile_low, rest} do
+        {1, 1, <<_reserved::1, rest::bitstring>>} -> rest
+        _other -> rest
+      end
+
+    match?(<<0::1, 0::1, _rest::bitstring>>, rest)
+  end
+
+  defp keyframe?(_payload), do: false
+
+  @spec split(binary(), pos_integer()) :: [binary()]
+  defp split(payload, chunk_size) when byte_size(payload) <= chunk_size, do: [payload]
+
+  defp split(payload, chunk_size) do
+    <<chunk::binary-size(chunk_size), rest::binary>> = payload
+    [chunk | split(rest, chunk_size)]
+  end
+end
@@ -0,0 +1,448 @@
+defmodule Membrane.LiveCompositor.Scene do
+  @moduledoc """
+  Components that can be used to define a scene rendered on a video output.
+
+  Scene is a tree of components, where root of the tree is passed in the `:root` field of
+  `Membrane.LiveCompositor.Request.UpdateVideoOutput` or in the `:initial` option of the
+  `:video_output` pad. Concept of a component is explained
+  [here](https://compositor.live/docs/concept/component).
+
+  ```
+  %Scene.Tiles{
+    id: "tiles",
+    children: [
+      %Scene.InputStream{input_id: "input_0"},
+      %Scene.InputStream{input_id: "input_1"}
+    ]
+  }
+  ```
+  """
+
+  alias __MODULE__.{Image, InputStream, Rescaler, Shader, Text, Tiles, View, WebView}
+
+  @typedoc """
+  Any component that can be part of a scene.
+  """
+  @type component ::
+          View.t()
+          | Tiles.t()
+          | Rescaler.t()
+          | InputStream.t()
+          | Shader.t()
+          | Image.t()
+          | Text.t()
+          | WebView.t()
+
+  @typedoc """
+  Id of a component. Components with the same id in the consecutive scene updates are treated
+  as the same component, e.g. transitions are only possible between components with the same id.
+  """
+  @type component_id :: String.t()
+
+  @typedoc """
+  Color in a `"#RRGGBBAA"` or `"#RRGGBB"` format.
+  """
+  @type rgba_color :: String.t()
+
+  @typedoc """
+  Easing function used by a transition. [Learn more](https://compositor.live/docs/api/components/View#easingfunction)
+  """
+  @type easing_function ::
+          %{function_name: :linear | :bounce}
+          | %{function_name: :cubic_bezier, points: [number()]}
+
+  @typedoc """
+  Defines how a component should transition to its new state after a scene update. Only components
+  with `id` defined can be transitioned.
+
+  - `:duration_ms` - Duration of the transition in milliseconds.
+  - `:easing_function` - Easing function used for the transition. Defaults to linear.
+  """
+  @type transition :: %{
+          required(:duration_ms) => non_neg_integer(),
+          optional(:easing_function) => easing_function()
+        }
+
+  @typedoc """
+  Reason why a scene was rejected by the LiveCompositor bin before it was sent to the server.
+
+  - `{:unknown_input, input_id}` - `InputStream` references a video input that is not connected
+  to the bin.
+  - `{:unknown_shader, shader_id}` - `Shader` references a shader that was not registered via
+  this bin.
+  - `{:unknown_image, image_id}` - `Image` references an image that was not registered via
+  this bin.
+  - `{:unknown_web_renderer, instance_id}` - `WebView` references a web renderer instance that
+  was not registered via this bin.
+  - `{:duplicate_component_id, id}` - More than one component in the scene uses the same `id`.
+  - `{:transition_without_id, component}` - Component of type `component` defines `transition`,
+  but does not have an `id`.
+  """
+  @type validation_error ::
+          {:unknown_input, Membrane.LiveCompositor.input_id()}
+          | {:unknown_shader, String.t()}
+          | {:unknown_image, String.t()}
+          | {:unknown_web_renderer, String.t()}
+          | {:duplicate_component_id, component_id()}
+          | {:transition_without_id, module()}
+
+  @type horizontal_align :: :left | :right | :justified | :center
+  @type vertical_align :: :top | :bottom | :justified | :center
+
+  defmodule View do
+    @moduledoc """
+    Core layout component, used to position and arrange its children.
+    See [`View`](https://compositor.live/docs/api/components/View) documentation.
+    """
+
+    alias Membrane.LiveCompositor.Scene
+
+    defstruct id: nil,
+              children: [],
+              width: nil,
+              height: nil,
+              direction: nil,
+              top: nil,
+              left: nil,
+              bottom: nil,
+              right: nil,
+              rotation: nil,
+              transition: nil,
+              overflow: nil,
+              background_color_rgba: nil
+
+    @typedoc """
+    - Fields `:top`, `:left`, `:bottom`, `:right` and `:rotation` make a view absolutely positioned
+    inside its parent. Absolutely positioned view needs to have `:width` and `:height` defined.
+    - `:direction` - Direction in which children are placed. Defaults to `:row`.
+    - `:overflow` - Behavior of children that do not fit inside the view. Defaults to `:hidden`.
+    """
+    @type t :: %__MODULE__{
+            id: Scene.component_id() | nil,
+            children: [Scene.component()],
+            width: number() | nil,
+            height: number() | nil,
+            direction: :row | :column | nil,
+            top: number() | nil,
+            left: number() | nil,
+            bottom: number() | nil,
+            right: number() | nil,
+            rotation: number() | nil,
+            transition: Scene.transition() | nil,
+            overflow: :visible | :hidden | :fit | nil,
+            background_color_rgba: Scene.rgba_color() | nil
+          }
+  end
+
+  defimpl Jason.Encoder, for: View do
+    @spec encode(View.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(Map.from_struct(value) |> Map.put(:type, :view), opts)
+    end
+  end
+
+  defmodule Tiles do
+    @moduledoc """
+    Layout component that arranges its children on a grid of equally sized tiles.
+    See [`Tiles`](https://compositor.live/docs/api/components/Tiles) documentation.
+    """
+
+    alias Membrane.LiveCompositor.Scene
+
+    defstruct id: nil,
+              children: [],
+              width: nil,
+              height: nil,
+              background_color_rgba: nil,
+              tile_aspect_ratio: nil,
+              margin: nil,
+              padding: nil,
+              horizontal_align: nil,
+              vertical_align: nil,
+              transition: nil
+
+    @typedoc """
+    - `:tile_aspect_ratio` - Aspect ratio of a tile in `"W:H"` format. Defaults to `"16:9"`.
+    - `:margin` - Margin of each tile in pixels.
+    - `:padding` - Padding on each tile in pixels.
+    """
+    @type t :: %__MODULE__{
+            id: Scene.component_id() | nil,
+            children: [Scene.component()],
+            width: number() | nil,
+            height: number() | nil,
+            background_color_rgba: Scene.rgba_color() | nil,
+            tile_aspect_ratio: String.t() | nil,
+            margin: number() | nil,
+            padding: number() | nil,
+            horizontal_align: Scene.horizontal_align() | nil,
+            vertical_align: Scene.vertical_align() | nil,
+            transition: Scene.transition() | nil
+          }
+  end
+
+  defimpl Jason.Encoder, for: Tiles do
+    @spec encode(Tiles.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(Map.from_struct(value) |> Map.put(:type, :tiles), opts)
+    end
+  end
+
+  defmodule Rescaler do
+    @moduledoc """
+    Component that resizes its child to fit into the specified area.
+    See [`Rescaler`](https://compositor.live/docs/api/components/Rescaler) documentation.
+    """
+
+    alias Membrane.LiveCompositor.Scene
+
+    @enforce_keys [:child]
+    defstruct @enforce_keys ++
+                [
+                  id: nil,
+                  mode: nil,
+                  horizontal_align: nil,
+                  vertical_align: nil,
+                  width: nil,
+                  height: nil,
+                  top: nil,
+                  left: nil,
+                  bottom: nil,
+                  right: nil,
+                  rotation: nil,
+                  transition: nil
+                ]
+
+    @typedoc """
+    - `:mode` - Resize mode. Defaults to `:fit`.
+    - Fields `:top`, `:left`, `:bottom`, `:right` and `:rotation` make a rescaler absolutely
+    positioned inside its parent. Absolutely positioned rescaler needs to have `:width` and
+    `:height` defined.
+    """
+    @type t :: %__MODULE__{
+            id: Scene.component_id() | nil,
+            child: Scene.component(),
+            mode: :fit | :fill | nil,
+            horizontal_align: Scene.horizontal_align() | nil,
+            vertical_align: Scene.vertical_align() | nil,
+            width: number() | nil,
+            height: number() | nil,
+            top: number() | nil,
+            left: number() | nil,
+            bottom: number() | nil,
+            right: number() | nil,
+            rotation: number() | nil,
+            transition: Scene.transition() | nil
+          }
+  end
+
+  defimpl Jason.Encoder, for: Rescaler do
+    @spec encode(Rescaler.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(Map.from_struct(value) |> Map.put(:type, :rescaler), opts)
+    end
+  end
+
+  defmodule InputStream do
+    @moduledoc """
+    Component that renders frames of an input stream.
+    See [`InputStream`](https://compositor.live/docs/api/components/InputStream) documentation.
+    """
+
+    alias Membrane.LiveCompositor
+    alias Membrane.LiveCompositor.Scene
+
+    @enforce_keys [:input_id]
+    defstruct @enforce_keys ++ [id: nil]
+
+    @type t :: %__MODULE__{
+            id: Scene.component_id() | nil,
+            input_id: LiveCompositor.input_id()
+          }
+  end
+
+  defimpl Jason.Encoder, for: InputStream do
+    @spec encode(InputStream.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(Map.from_struct(value) |> Map.put(:type, :input_stream), opts)
+    end
+  end
+
+  defmodule Shader do
+    @moduledoc """
+    Component that renders its children with a registered shader.
+    See [`Shader`](https://compositor.live/docs/api/components/Shader) documentation.
+    """
+
+    alias Membrane.LiveCompositor.Scene
+
+    @enforce_keys [:shader_id, :resolution]
+    defstruct @enforce_keys ++ [id: nil, children: [], shader_param: nil]
+
+    @typedoc """
+    - `:shader_id` - Id of a shader registered with `Membrane.LiveCompositor.Request.RegisterShader`.
+    - `:shader_param` - Parameters passed to the shader. [Learn more](https://compositor.live/docs/api/components/Shader#shaderparam)
+    - `:resolution` - Resolution of a texture produced by the shader.
+    """
+    @type t :: %__MODULE__{
+            id: Scene.component_id() | nil,
+            children: [Scene.component()],
+            shader_id: String.t(),
+            shader_param: map() | nil,
+            resolution: %{width: non_neg_integer(), height: non_neg_integer()}
+          }
+  end
+
+  defimpl Jason.Encoder, for: Shader do
+    @spec encode(Shader.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(Map.from_struct(value) |> Map.put(:type, :shader), opts)
+    end
+  end
+
+  defmodule Image do
+    @moduledoc """
+    Component that renders a registered image.
+    See [`Image`](https://compositor.live/docs/api/components/Image) documentation.
+    """
+
+    alias Membrane.LiveCompositor.Scene
+
+    @enforce_keys [:image_id]
+    defstruct @enforce_keys ++ [id: nil]
+
+    @typedoc """
+    - `:image_id` - Id of an image registered with `Membrane.LiveCompositor.Request.RegisterImage`.
+    """
+    @type t :: %__MODULE__{
+            id: Scene.component_id() | nil,
+            image_id: String.t()
+          }
+  end
+
+  defimpl Jason.Encoder, for: Image do
+    @spec encode(Image.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(Map.from_struct(value) |> Map.put(:type, :image), opts)
+    end
+  end
+
+  defmodule Text do
+    @moduledoc """
+    Component that renders text.
+    See [`Text`](https://compositor.live/docs/api/components/Text) documentation.
+    """
+
+    alias Membrane.LiveCompositor.Scene
+
+    @enforce_keys [:text, :font_size]
+    defstruct @enforce_keys ++
+                [
+                  id: nil,
+                  width: nil,
+                  height: nil,
+                  max_width: nil,
+                  max_height: nil,
+                  line_height: nil,
+                  color_rgba: nil,
+                  background_color_rgba: nil,
+                  font_family: nil,
+                  style: nil,
+                  align: nil,
+                  wrap: nil,
+                  weight: nil
+                ]
+
+    @typedoc """
+    - `:font_size` - Font size in pixels.
+    - `:font_family` - Name of a font family. Defaults to `"Verdana"`.
+    """
+    @type t :: %__MODULE__{
+            id: Scene.component_id() | nil,
+            text: String.t(),
+            font_size: number(),
+            width: number() | nil,
+            height: number() | nil,
+            max_width: number() | nil,
+            max_height: number() | nil,
+            line_height: number() | nil,
+            color_rgba: Scene.rgba_color() | nil,
+            background_color_rgba: Scene.rgba_color() | nil,
+            font_family: String.t() | nil,
+            style: :normal | :italic | :oblique | nil,
+            align: :left | :right | :justified | :center | nil,
+            wrap: :none | :glyph | :word | nil,
+            weight:
+              :thin
+              | :extra_light
+              | :light
+              | :normal
+              | :medium
+              | :semi_bold
+              | :bold
+              | :extra_bold
+              | :black
+              | nil
+          }
+  end
+
+  defimpl Jason.Encoder, for: Text do
+    @spec encode(Text.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(Map.from_struct(value) |> Map.put(:type, :text), opts)
+    end
+  end
+
+  defmodule WebView do
+    @moduledoc """
+    Component that renders a website using a registered web renderer instance.
+    See [`WebView`](https://compositor.live/docs/api/components/WebView) documentation.
+    """
+
+    alias Membrane.LiveCompositor.Scene
+
+    @enforce_keys [:instance_id]
+    defstruct @enforce_keys ++ [id: nil, children: []]
+
+    @typedoc """
+    - `:instance_id` - Id of a web renderer instance registered with
+    `Membrane.LiveCompositor.Request.RegisterWebRenderer`.
+    - `:children` - Components that can be embedded into the website.
+    """
+    @type t :: %__MODULE__{
+            id: Scene.component_id() | nil,
+            children: [Scene.component()],
+            instance_id: String.t()
+          }
+  end
+
+  defimpl Jason.Encoder, for: WebView do
+    @spec encode(WebView.t(), Jason.Encode.opts()) :: iodata
+    def encode(value, opts) do
+      Jason.Encode.map(Map.from_struct(value) |> Map.put(:type, :web_view), opts)
+    end
+  end
+
+  @doc false
+  @spec children(component() | any()) :: [component() | any()]
+  def children(%Rescaler{child: child}), do: [child]
+
+  def children(%module{children: children}) when module in [View, Tiles, Shader, WebView],
+    do: children
+
+  def children(_component), do: []
+
+  @doc false
+  @spec map_children(component() | any(), (component() | any() -> component() | any())) ::
+          component() | any()
+  def map_children(component = %Rescaler{child: child}, fun) do
+    %Rescaler{component | child: fun.(child)}
+  end
+
+  def map_children(component = %module{children: children}, fun)
+      when module in [View, Tiles, Shader, WebView] do
+    %{component | children: Enum.map(children, fun)}
+  end
+
+  def map_children(component, _fun), do: component
+end
@@ -0,0 +1,59 @@
+defmodule Membrane.LiveCompositor.SceneTransitions do
+  @moduledoc false
+  # Injects transitions into a scene, based on the scene that was previously sent
+  # for the same output.
+
+  alias Membrane.LiveCompositor.{Request, Scene}
+
+  @transitionable [Scene.View, Scene.Tiles, Scene.Rescaler]
+
+  # Adds `transition` to every View, Tiles and Rescaler in `root` that has an `id` that was
+  # also used in `previous_root`. Components that already define `transition` are not modified.
+  @spec inject(
+          Scene.component(),
+          Scene.component() | nil,
+          Request.UpdateVideoOutput.transition() | nil
+        ) :: Scene.component()
+  def inject(root, _previous_root, nil), do: root
+  def inject(root, nil, _transition), do: root
+
+  def inject(root, previous_root, {duration, easing_function}) do
+    duration_ms = Membrane.Time.as_milliseconds(duration, :round)
+
+    transition =
+      case easing_function do
+        nil -> %{duration_ms: duration_ms}
+        easing_function -> %{duration_ms: duration_ms, easing_function: easing_function}
+      end
+
+    previous_ids = collect_ids(previous_root, MapSet.new())
+    inject_transition(root, previous_ids, transition)
+  end
+
+  defp inject_transition(component = %module{id: id, transition: nil}, previous_ids, transition)
+       when module in @transitionable and id != nil do
+    component =
+      if MapSet.member?(previous_ids, id),
+        do: %{component | transition: transition},
+        else: component
+
+    Scene.map_children(component, &inject_transition(&1, previous_ids, transition))
+  end
+
+  defp inject_transition(component, previous_ids, transition) do
+    Scene.map_children(component, &inject_transition(&1, previous_ids, transition))
+  end
+
+  defp collect_ids(component = %module{id: id}, ids)
+       when module in @transitionable and id != nil do
+    component
+    |> Scene.children()
+    |> Enum.reduce(MapSet.put(ids, id), &collect_ids/2)
+  end
+
+  defp collect_ids(component, ids) do
+    component
+    |> Scene.children()
+    |> Enum.reduce(ids, &collect_ids/2)
+  end
+end
@@ -0,0 +1,95 @@
+defmodule Membrane.LiveCompositor.SceneValidator do
+  @moduledoc false
+  # Validates scene against the state of the bin, so obviously invalid scenes
+  # can be rejected without sending them to the LiveCompositor server.
+  #
+  # Only components defined in `Membrane.LiveCompositor.Scene` are validated,
+  # other terms (e.g. plain maps) are passed to the server as is.
+
+  alias Membrane.LiveCompositor.{Scene, State}
+
+  @components [
+    Scene.View,
+    Scene.Tiles,
+    Scene.Rescaler,
+    Scene.InputStream,
+    Scene.Shader,
+    Scene.Image,
+    Scene.Text,
+    Scene.WebView
+  ]
+
+  @spec validate(Scene.component(), State.t()) :: :ok | {:error, [Scene.validation_error()]}
+  def validate(root, state) do
+    {errors, _ids} = validate_component(root, state, {[], MapSet.new()})
+
+    case Enum.reverse(errors) do
+      [] -> :ok
+      errors -> {:error, errors}
+    end
+  end
+
+  defp validate_component(component = %module{}, state, acc) when module in @components do
+    acc =
+      acc
+      |> check_id(component)
+      |> check_transition(component)
+      |> check_references(component, state)
+
+    component
+    |> Scene.children()
+    |> Enum.reduce(acc, fn child, acc -> validate_component(child, state, acc) end)
+  end
+
+  defp validate_component(_other, _state, acc), do: acc
+
+  defp check_id({errors, ids}, %{id: nil}), do: {errors, ids}
+
+  defp check_id({errors, ids}, %{id: id}) do
+    if MapSet.member?(ids, id) do
+      {[{:duplicate_component_id, id} | errors], ids}
+    else
+      {errors, MapSet.put(ids, id)}
+    end
+  end
+
+  defp check_transition({errors, ids}, %module{id: nil, transition: transition})
+       when transition != nil do
+    {[{:transition_without_id, module} | errors], ids}
+  end
+
+  defp check_transition(acc, _component), do: acc
+
+  defp check_references({errors, ids}, component, state) do
+    case reference_error(component, state) do
+      nil -> {errors, ids}
+      error -> {[error | errors], ids}
+    end
+  end
+
+  defp reference_error(%Scene.InputStream{input_id: input_id}, state) do
+    if input_id in state.context.video_inputs or input_id in state.context.mp4_inputs,
+      do: nil,
+      else: {:unknown_input, input_id}
+  end
+
+  defp reference_error(%Scene.Shader{shader_id: shader_id}, state) do
+    if shader_id in state.context.shaders,
+      do: nil,
+      else: {:unknown_shader, shader_id}
+  end
+
+  defp reference_error(%Scene.Image{image_id: image_id}, state) do
+    if image_id in state.context.images,
+      do: nil,
+      else: {:unknown_image, image_id}
+  end
+
+  defp reference_error(%Scene.WebView{instance_id: instance_id}, state) do
+    if instance_id in state.context.web_renderers,
+      do: nil,
+      else: {:unknown_web_renderer, instance_id}
+  end
+
+  defp reference_error(_component, _state), do: nil
+end
@@ -0,0 +1,95 @@
+defmodule Membrane.LiveCompositor.ServerMonitor do
+  @moduledoc false
+  # Watches the LiveCompositor server used by the bin and sends
+  # `{:live_compositor_server_down, reason}` to the bin when the server stops working.
+  #
+  # Server started by the bin is monitored directly. Server started by someone else is
+  # polled on the `/status` endpoint. It's considered down after a few consecutive failed
+  # checks or when its instance id changes, i.e. it was restarted and lost all registered
+  # inputs, outputs and renderers.
+  #
+  # The bin can also ask the monitor to confirm that the server is working with
+  # `confirm_server_up/2`. The monitor checks the `/status` endpoint until it succeeds and then
+  # sends `{:live_compositor_server_up, tag}` to the bin, so the bin doesn't wait for the HTTP
+  # request in its callbacks.
+
+  use GenServer, restart: :temporary
+
+  alias Membrane.LiveCompositor.{ApiClient, RequestPolicy}
+
+  @status_check_interval_ms 1_000
+  @max_failed_status_checks 3
+
+  @spec confirm_server_up(pid(), term()) :: :ok
+  def confirm_server_up(monitor, tag) do
+    send(monitor, {:confirm_server_up, tag})
+    :ok
+  end
+
+  @spec start_link({{:inet.ip_address(), :inet.port_number()}, pid() | nil, pid()}) ::
+          GenServer.on_start()
+  def start_link({lc_address, server_pid, bin_pid}) do
+    GenServer.start_link(__MODULE__, {lc_address, server_pid, bin_pid})
+  end
+
+  @impl true
+  def init({lc_address, server_pid, bin_pid}) when server_pid != nil do
+    Process.monitor(server_pid)
+    {:ok, %{bin_pid: bin_pid, lc_address: lc_address}}
+  end
+
+  @impl true
+  def init({lc_address, nil, bin_pid}) do
+    Process.send_after(self(), :check_status, @status_check_interval_ms)
+
+    {:ok, %{bin_pid: bin_pid, lc_address: lc_address, instance_id: nil, failed_checks: 0}}
+  end
+
+  @impl true
+  def handle_info({:DOWN, _ref, :process, _pid, reason}, state) do
+    server_down({:server_exited, reason}, state)
+  end
+
+  @impl true
+  def handle_info(:check_status, state) do
+    case get_status(state) do
+      {:ok, %{"instance_id" => instance_id}}
+      when state.instance_id != nil and instance_id != state.instance_id ->
+        server_down({:instance_changed, state.instance_id, instance_id}, state)
+
+      {:ok, status} ->
+        Process.send_after(self(), :check_status, @status_check_interval_ms)
+        {:noreply, %{state | instance_id: status["instance_id"], failed_checks: 0}}
+
+      {:error, error} when state.failed_checks + 1 >= @max_failed_status_checks ->
+        server_down({:status_check_failed, error}, state)
+
+      {:error, _error} ->
+        Process.send_after(self(), :check_status, @status_check_interval_ms)
+        {:noreply, %{state | failed_checks: state.failed_checks + 1}}
+    end
+  end
+
+  @impl true
+  def handle_info({:confirm_server_up, tag}, state) do
+    case get_status(state) do
+      {:ok, _status} ->
+        send(state.bin_pid, {:live_compositor_server_up, tag})
+
+      {:error, _error} ->
+        Process.send_after(self(), {:confirm_server_up, tag}, @status_check_interval_ms)
+    end
+
+    {:noreply, state}
+  end
+
+  defp get_status(state) do
+    policy = %RequestPolicy{timeout: Membrane.Time.seconds(2), retry: :none}
+    ApiClient.get_status(state.lc_address, policy: policy)
+  end
+
+  defp server_down(reason, state) do
+    send(state.bin_pid, {:live_compositor_server_down, reason})
+    {:stop, :normal, state}
+  end
+end
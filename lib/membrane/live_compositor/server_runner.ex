@@ -21,7 +21,8 @@ defmodule Membrane.LiveCompositor.ServerRunner do
         to_string(opt.composing_strategy == :offline_processing),
       "LIVE_COMPOSITOR_OUTPUT_FRAMERATE" => framerate_str,
       "LIVE_COMPOSITOR_STREAM_FALLBACK_TIMEOUT_MS" =>
-        to_string(Membrane.Time.as_milliseconds(opt.stream_fallback_timeout, :round))
+        to_string(Membrane.Time.as_milliseconds(opt.stream_fallback_timeout, :round)),
+      "LIVE_COMPOSITOR_WEB_RENDERER_ENABLE" => to_string(opt.web_renderer_enabled?)
     }
 
     case opt.server_setup do
@@ -161,8 +162,7 @@ defmodule Membrane.LiveCompositor.ServerRunner do
   defp start_on_port(lc_port, env, bin_path, instance_id, utility_supervisor) do
     env =
       Map.merge(env, %{
-        "LIVE_COMPOSITOR_API_PORT" => to_string(lc_port),
-        "LIVE_COMPOSITOR_WEB_RENDERER_ENABLE" => "false"
+        "LIVE_COMPOSITOR_API_PORT" => to_string(lc_port)
       })
 
     spec =
@@ -191,8 +191,8 @@ defmodule Membrane.LiveCompositor.ServerRunner do
       Process.sleep(100)
 
       with {:is_alive, true} <- {:is_alive, Process.alive?(pid)},
-           {:ok, response} <- ApiClient.get_status({@local_host, lc_port}) do
-        if response.body["instance_id"] == instance_id do
+           {:ok, status} <- ApiClient.get_status({@local_host, lc_port}) do
+        if status["instance_id"] == instance_id do
           {:halt, :started}
         else
           {:halt, :not_started}
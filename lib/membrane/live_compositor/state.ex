@@ -2,25 +2,82 @@ defmodule Membrane.LiveCompositor.State do
   @moduledoc false
 
   require Membrane.Pad
-  alias Membrane.LiveCompositor
-  alias Membrane.LiveCompositor.Context
+  alias Membrane.{LiveCompositor, Pad}
+  alias Membrane.LiveCompositor.{Context, Request, RequestPolicy, Scene, Timeline}
 
   @enforce_keys [
+    :options,
     :output_framerate,
     :output_sample_rate,
     :lc_address,
     :context,
     :composing_strategy
   ]
-  defstruct @enforce_keys ++ [server_pid: nil, last_ssrc: 0]
+  defstruct @enforce_keys ++
+              [
+                server_pid: nil,
+                request_policy: %RequestPolicy{},
+                api_base_url: nil,
+                last_ssrc: 0,
+                video_scenes: %{},
+                audio_mixes: %{},
+                pending_updates: %{},
+                image_server: nil,
+                composing_started_at: nil,
+                output_pts: nil,
+                timelines: %{},
+                setup_completed?: false,
+                queued_messages: [],
+                on_server_crash: :notify,
+                server_down?: false,
+                server_monitor: nil,
+                event_handler_monitor: nil,
+                server_generation: 0,
+                pad_ports: %{},
+                udp_sockets: %{},
+                registrations: %{}
+              ]
 
   @type t :: %__MODULE__{
+          options: LiveCompositor.t(),
           context: Context.t(),
           output_framerate: Membrane.RawVideo.framerate(),
           output_sample_rate: LiveCompositor.output_sample_rate(),
           composing_strategy: :real_time_auto_init | :real_time | :offline_processing,
           lc_address: {ip :: :inet.ip_address(), :inet.port_number()},
-          server_pid: pid() | nil
+          server_pid: pid() | nil,
+          request_policy: RequestPolicy.t(),
+          api_base_url: String.t() | nil,
+          video_scenes: %{LiveCompositor.output_id() => Scene.component()},
+          audio_mixes: %{LiveCompositor.output_id() => [Request.UpdateAudioOutput.input()]},
+          pending_updates: %{
+            {:video_scenes | :audio_mixes, LiveCompositor.output_id()} => [
+              {Membrane.Time.t(), Scene.component() | [Request.UpdateAudioOutput.input()]}
+            ]
+          },
+          image_server: pid() | nil,
+          composing_started_at: Membrane.Time.t() | nil,
+          output_pts: Membrane.Time.t() | nil,
+          timelines: %{
+            Timeline.id() => %{
+              cues: %{
+                Timeline.cue_id() => %{cue: Timeline.Cue.t(), status: Timeline.cue_status()}
+              },
+              timer: reference() | nil
+            }
+          },
+          setup_completed?: boolean(),
+          queued_messages: [{:notification, any()} | {:info, any()}],
+          on_server_crash: :terminate | :notify | :restart,
+          server_down?: boolean(),
+          server_monitor: pid() | nil,
+          event_handler_monitor: reference() | nil,
+          server_generation: non_neg_integer(),
+          pad_ports: %{Pad.ref() => :inet.port_number()},
+          udp_sockets: %{Pad.ref() => :gen_udp.socket()},
+          registrations: %{
+            (Context.renderer() | {:mp4_input, LiveCompositor.input_id()}) => Request.t()
+          }
         }
 
   @spec next_ssrc(t()) :: {t(), non_neg_integer()}
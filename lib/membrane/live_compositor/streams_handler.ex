@@ -2,17 +2,17 @@ defmodule Membrane.LiveCompositor.StreamsHandler do
   @moduledoc false
 
   alias Membrane.LiveCompositor
-  alias Membrane.LiveCompositor.{ApiClient, State}
+  alias Membrane.LiveCompositor.{ApiClient, RequestPolicy, State}
 
   @spec register_video_input_stream(String.t(), map(), State.t()) ::
           {:ok, :inet.port_number()} | {:error, any()}
   def register_video_input_stream(pad_id, input_pad_opts, state) do
     body = %{
       type: :rtp_stream,
-      transport_protocol: :tcp_server,
+      transport_protocol: transport_protocol(input_pad_opts.transport),
       port: input_pad_opts.port |> map_port,
       video: %{
-        decoder: :ffmpeg_h264
+        decoder: video_decoder(input_pad_opts.video_codec)
       },
       required: input_pad_opts.required,
       offset_ms:
@@ -25,7 +25,7 @@ defmodule Membrane.LiveCompositor.StreamsHandler do
     encoded_id = URI.encode_www_form(pad_id)
 
     {:post, "/api/input/#{encoded_id}/register", body}
-    |> ApiClient.send_request(state.lc_address)
+    |> ApiClient.send_request(state.lc_address, api_opts(state))
     |> map_response
   end
 
@@ -34,7 +34,7 @@ defmodule Membrane.LiveCompositor.StreamsHandler do
   def register_audio_input_stream(pad_id, input_pad_opts, state) do
     body = %{
       type: :rtp_stream,
-      transport_protocol: :tcp_server,
+      transport_protocol: transport_protocol(input_pad_opts.transport),
       port: input_pad_opts.port |> map_port,
       audio: %{
         decoder: :opus
@@ -50,54 +50,110 @@ defmodule Membrane.LiveCompositor.StreamsHandler do
     encoded_id = URI.encode_www_form(pad_id)
 
     {:post, "/api/input/#{encoded_id}/register", body}
-    |> ApiClient.send_request(state.lc_address)
+    |> ApiClient.send_request(state.lc_address, api_opts(state))
     |> map_response
   end
 
-  @spec register_video_output_stream(String.t(), map(), State.t()) ::
+  @spec register_video_output_stream(String.t(), map(), State.t(), :inet.port_number() | nil) ::
           {:ok, :inet.port_number()} | {:error, any()}
-  def register_video_output_stream(pad_id, output_pad_opts, state) do
-    body = %{
-      type: :rtp_stream,
-      transport_protocol: :tcp_server,
-      port: output_pad_opts.port |> map_port,
-      video: %{
-        send_eos_when: map_eos_cond(output_pad_opts.send_eos_when),
-        resolution: %{
-          width: output_pad_opts.width,
-          height: output_pad_opts.height
-        },
-        encoder: output_pad_opts.encoder,
-        initial: output_pad_opts.initial
-      }
-    }
+  def register_video_output_stream(pad_id, output_pad_opts, state, udp_port) do
+    with {:ok, transport} <- output_transport(output_pad_opts, state, udp_port) do
+      body =
+        Map.merge(transport, %{
+          type: :rtp_stream,
+          video: %{
+            send_eos_when: map_eos_cond(output_pad_opts.send_eos_when),
+            resolution: %{
+              width: output_pad_opts.width,
+              height: output_pad_opts.height
+            },
+            encoder: output_pad_opts.encoder,
+            initial: output_pad_opts.initial
+          }
+        })
+
+      encoded_id = URI.encode_www_form(pad_id)
+
+      {:post, "/api/output/#{encoded_id}/register", body}
+      |> ApiClient.send_request(state.lc_address, api_opts(state))
+      |> map_response(transport)
+    end
+  end
 
-    encoded_id = URI.encode_www_form(pad_id)
+  @spec register_audio_output_stream(String.t(), map(), State.t(), :inet.port_number() | nil) ::
+          {:ok, :inet.port_number()} | {:error, any()}
+  def register_audio_output_stream(pad_id, output_pad_opts, state, udp_port) do
+    with {:ok, transport} <- output_transport(output_pad_opts, state, udp_port) do
+      body =
+        Map.merge(transport, %{
+          type: :rtp_stream,
+          audio: %{
+            send_eos_when: map_eos_cond(output_pad_opts.send_eos_when),
+            encoder: output_pad_opts.encoder,
+            initial: output_pad_opts.initial
+          }
+        })
+
+      encoded_id = URI.encode_www_form(pad_id)
+
+      {:post, "/api/output/#{encoded_id}/register", body}
+      |> ApiClient.send_request(state.lc_address, api_opts(state))
+      |> map_response(transport)
+    end
+  end
 
-    {:post, "/api/output/#{encoded_id}/register", body}
-    |> ApiClient.send_request(state.lc_address)
-    |> map_response
+  @spec video_decoder(LiveCompositor.video_codec()) :: :ffmpeg_h264 | :ffmpeg_vp8 | :ffmpeg_vp9
+  defp video_decoder(:h264), do: :ffmpeg_h264
+  defp video_decoder(:vp8), do: :ffmpeg_vp8
+  defp video_decoder(:vp9), do: :ffmpeg_vp9
+
+  @spec transport_protocol(LiveCompositor.transport()) :: :tcp_server | :udp
+  defp transport_protocol(:tcp), do: :tcp_server
+  defp transport_protocol(:udp), do: :udp
+
+  # Opens a UDP socket on a free port from the range. The socket is kept open by the bin until
+  # it's handed over to the UDP source, so the port can't be taken by another process after
+  # it's sent to the server.
+  @spec open_udp_socket(:inet.port_number() | LiveCompositor.port_range()) ::
+          {:ok, :gen_udp.socket(), :inet.port_number()} | {:error, :no_free_udp_port}
+  def open_udp_socket(port) do
+    {lower_bound, upper_bound} =
+      case port do
+        {start, endd} -> {start, endd}
+        exact -> {exact, exact}
+      end
+
+    lower_bound..upper_bound
+    |> Enum.shuffle()
+    |> Enum.find_value({:error, :no_free_udp_port}, fn port ->
+      case :gen_udp.open(port, [:binary, active: false]) do
+        {:ok, socket} -> {:ok, socket, port}
+        {:error, _reason} -> nil
+      end
+    end)
   end
 
-  @spec register_audio_output_stream(String.t(), map(), State.t()) ::
-          {:ok, :inet.port_number()} | {:error, any()}
-  def register_audio_output_stream(pad_id, output_pad_opts, state) do
-    body = %{
-      type: :rtp_stream,
-      transport_protocol: :tcp_server,
-      port: output_pad_opts.port |> map_port,
-      audio: %{
-        send_eos_when: map_eos_cond(output_pad_opts.send_eos_when),
-        encoder: output_pad_opts.encoder,
-        initial: output_pad_opts.initial
-      }
-    }
+  # Over TCP the pipeline connects to the port opened by the server. Over UDP the server
+  # sends the output stream to the address of the pipeline, on the port of the socket opened
+  # by the bin with `open_udp_socket/1`.
+  @spec output_transport(map(), State.t(), :inet.port_number() | nil) ::
+          {:ok, map()} | {:error, any()}
+  defp output_transport(%{transport: :tcp, port: port}, _state, _udp_port) do
+    {:ok, %{transport_protocol: :tcp_server, port: map_port(port)}}
+  end
 
-    encoded_id = URI.encode_www_form(pad_id)
+  defp output_transport(%{transport: :udp}, state, udp_port) do
+    local_ip = ApiClient.local_ip_towards(state.lc_address)
+    {:ok, %{transport_protocol: :udp, port: udp_port, ip: to_string(:inet.ntoa(local_ip))}}
+  end
 
-    {:post, "/api/output/#{encoded_id}/register", body}
-    |> ApiClient.send_request(state.lc_address)
-    |> map_response
+  @spec api_opts(State.t()) :: [ApiClient.send_opt()]
+  defp api_opts(state) do
+    [
+      policy: state.request_policy,
+      retry?: RequestPolicy.retry?(state.request_policy, :internal),
+      base_url: state.api_base_url
+    ]
   end
 
   @spec map_eos_cond(LiveCompositor.send_eos_condition()) :: any()
@@ -120,10 +176,11 @@ defmodule Membrane.LiveCompositor.StreamsHandler do
     end
   end
 
-  defp map_response(response) do
-    case response do
-      {:ok, response} -> {:ok, response.body["port"]}
-      {:error, err} -> {:error, err}
+  defp map_response(response, transport \\ nil) do
+    case {response, transport} do
+      {{:ok, _body}, %{transport_protocol: :udp, ip: _ip, port: port}} -> {:ok, port}
+      {{:ok, body}, _transport} -> {:ok, body["port"]}
+      {{:error, err}, _transport} -> {:error, err}
     end
   end
 end
@@ -0,0 +1,289 @@
+defmodule Membrane.LiveCompositor.Timeline do
+  @moduledoc """
+  Sequence of scene changes and other requests that should happen at specific moments.
+
+  Timeline is built from cues. Every cue has an id (unique within the timeline), a time and
+  a request that should be applied at that time. The whole timeline is submitted to the bin
+  with `Membrane.LiveCompositor.Request.SubmitTimeline`:
+
+  ```
+  timeline =
+    Timeline.new("intro", start: :now)
+    |> Timeline.show_scene("title", Membrane.Time.seconds(0), "output_0", title_scene)
+    |> Timeline.show_scene("main", Membrane.Time.seconds(5), "output_0", main_scene,
+      transition: {Membrane.Time.milliseconds(500), nil}
+    )
+    |> Timeline.fade_audio("music_out", Membrane.Time.seconds(5), "output_0",
+      from: [%{input_id: "music", volume: 1.0}],
+      to: [%{input_id: "music", volume: 0.0}],
+      duration: Membrane.Time.seconds(2)
+    )
+    |> Timeline.drop_input("drop_music", Membrane.Time.seconds(7), "music")
+
+  {[notify_child: {:live_compositor, %Request.SubmitTimeline{timeline: timeline}}], state}
+  ```
+
+  The bin converts cues to scheduled requests. LiveCompositor server can't cancel a request
+  that was already scheduled, so in real-time composing strategies the bin holds each cue and
+  sends it to the server shortly before its time. Until then the cue is `:pending` and it can be
+  cancelled with `Membrane.LiveCompositor.Request.CancelTimeline` or replaced by submitting
+  a timeline with the same id that contains a cue with the same id. Cues are not sent before
+  the composing is started.
+
+  When `composing_strategy` is `:offline_processing`, the time of the compositor doesn't depend
+  on the real time clock, so all cues are sent to the server as soon as the timeline is submitted
+  (or as soon as the composing is started) and they can't be cancelled.
+
+  When a cue takes effect, the bin notifies the parent with `t:cue_fired/0`. If the bin fails to
+  send a cue, it notifies the parent with `t:cue_failed/0` instead. `t:cue_fired/0` is not sent
+  when `composing_strategy` is `:offline_processing`.
+  """
+
+  alias Membrane.LiveCompositor
+  alias Membrane.LiveCompositor.{Request, Scene}
+
+  @typedoc """
+  Id of a timeline, uniquely identifies a timeline submitted to the bin.
+  """
+  @type id :: term()
+
+  @typedoc """
+  Id of a cue. Cues generated by `fade_audio/5` have ids `{:fade, cue_id, step}`.
+  """
+  @type cue_id :: term()
+
+  @typedoc """
+  Status of a cue tracked by the bin:
+  - `:pending` - The cue is held by the bin and wasn't sent to the server yet.
+  - `:scheduled` - The cue was sent to the server and it will take effect at its time.
+  - `:fired` - The cue took effect.
+  - `{:failed, reason}` - The bin failed to send the cue, `reason` is the same as in
+  `t:Membrane.LiveCompositor.Request.result/0`.
+  """
+  @type cue_status :: :pending | :scheduled | :fired | {:failed, reason :: any()}
+
+  @typedoc """
+  Notification sent to the parent when a cue took effect.
+  """
+  @type cue_fired :: {:timeline_cue_fired, id(), cue_id()}
+
+  @typedoc """
+  Notification sent to the parent when a cue couldn't be sent to the server.
+  """
+  @type cue_failed :: {:timeline_cue_failed, id(), cue_id(), reason :: any()}
+
+  @type notification :: cue_fired() | cue_failed()
+
+  @typedoc """
+  Requests that can be applied by a cue.
+  """
+  @type cue_request ::
+          Request.UpdateVideoOutput.t()
+          | Request.UpdateAudioOutput.t()
+          | Request.UnregisterInput.t()
+          | Request.UnregisterOutput.t()
+
+  @cue_requests [
+    Request.UpdateVideoOutput,
+    Request.UpdateAudioOutput,
+    Request.UnregisterInput,
+    Request.UnregisterOutput
+  ]
+
+  defmodule Cue do
+    @moduledoc """
+    Request that should be applied at a specific moment of the timeline.
+    """
+
+    alias Membrane.LiveCompositor.Timeline
+
+    @enforce_keys [:id, :at, :request]
+    defstruct @enforce_keys
+
+    @typedoc """
+    - `:id` - Id of the cue, unique within the timeline.
+    - `:at` - Time of the cue, relative to the start of the timeline.
+    - `:request` - Request applied at that time. Its `:schedule_time` is ignored.
+    """
+    @type t :: %__MODULE__{
+            id: Timeline.cue_id(),
+            at: Membrane.Time.t(),
+            request: Timeline.cue_request()
+          }
+  end
+
+  @enforce_keys [:id]
+  defstruct @enforce_keys ++ [start: 0, cues: []]
+
+  @typedoc """
+  - `:id` - Id of the timeline.
+  - `:start` - Start of the timeline. Time is measured from compositor start, the same way as
+  `:schedule_time` of requests. If `:now`, the timeline starts when it is received by the bin
+  (or when the composing is started if it wasn't started yet). When `composing_strategy` is
+  `:offline_processing`, `:now` is the same as `0`.
+  - `:cues` - Cues of the timeline.
+  """
+  @type t :: %__MODULE__{
+          id: id(),
+          start: Membrane.Time.t() | :now,
+          cues: [Cue.t()]
+        }
+
+  @typedoc """
+  - `:start` - See `t:t/0`. Defaults to `0`.
+  """
+  @type new_opt :: {:start, Membrane.Time.t() | :now}
+
+  @typedoc """
+  - `:transition` - See `Membrane.LiveCompositor.Request.UpdateVideoOutput`. Defaults to `nil`.
+  """
+  @type show_scene_opt :: {:transition, Request.UpdateVideoOutput.transition() | nil}
+
+  @typedoc """
+  - `:from` - Audio inputs at the beginning of the fade. Required.
+  - `:to` - Audio inputs at the end of the fade. Required.
+  - `:duration` - Duration of the fade. Required.
+  - `:steps` - Number of updates used to approximate the fade. Defaults to `10`.
+  """
+  @type fade_audio_opt ::
+          {:from, [Request.UpdateAudioOutput.input()]}
+          | {:to, [Request.UpdateAudioOutput.input()]}
+          | {:duration, Membrane.Time.t()}
+          | {:steps, pos_integer()}
+
+  @doc """
+  Creates an empty timeline.
+  """
+  @spec new(id(), [new_opt()]) :: t()
+  def new(id, opts \\ []) do
+    %__MODULE__{id: id, start: Keyword.get(opts, :start, 0)}
+  end
+
+  @doc """
+  Adds a cue that applies any supported request at `at`.
+  """
+  @spec add_cue(t(), cue_id(), Membrane.Time.t(), cue_request()) :: t()
+  def add_cue(timeline, cue_id, at, request) do
+    unless cue_request?(request) do
+      raise ArgumentError, "Request #{inspect(request)} can't be applied by a cue."
+    end
+
+    if Enum.any?(timeline.cues, &(&1.id == cue_id)) do
+      raise ArgumentError, "Cue #{inspect(cue_id)} is already defined in the timeline."
+    end
+
+    cue = %Cue{id: cue_id, at: at, request: request}
+    %__MODULE__{timeline | cues: timeline.cues ++ [cue]}
+  end
+
+  @doc """
+  Adds a cue that shows `root` on the video output at `at`.
+  """
+  @spec show_scene(
+          t(),
+          cue_id(),
+          Membrane.Time.t(),
+          LiveCompositor.output_id(),
+          Scene.component(),
+          [show_scene_opt()]
+        ) :: t()
+  def show_scene(timeline, cue_id, at, output_id, root, opts \\ []) do
+    request = %Request.UpdateVideoOutput{
+      output_id: output_id,
+      root: root,
+      transition: Keyword.get(opts, :transition)
+    }
+
+    add_cue(timeline, cue_id, at, request)
+  end
+
+  @doc """
+  Adds a cue that changes inputs mixed on the audio output at `at`.
+  """
+  @spec update_audio(
+          t(),
+          cue_id(),
+          Membrane.Time.t(),
+          LiveCompositor.output_id(),
+          [Request.UpdateAudioOutput.input()]
+        ) :: t()
+  def update_audio(timeline, cue_id, at, output_id, inputs) do
+    request = %Request.UpdateAudioOutput{output_id: output_id, inputs: inputs}
+    add_cue(timeline, cue_id, at, request)
+  end
+
+  @doc """
+  Adds cues that gradually change volumes of inputs on the audio output, starting at `at`.
+
+  Volume of every input is linearly interpolated between `:from` and `:to`. Inputs missing in one
+  of the lists are treated as if they had volume `0` there. Inputs with volume `0` at the end of
+  the fade are removed from the output.
+
+  The fade is split into `:steps` cues with ids `{:fade, cue_id, step}`. Cancelling `cue_id`
+  cancels all of them.
+  """
+  @spec fade_audio(
+          t(),
+          cue_id(),
+          Membrane.Time.t(),
+          LiveCompositor.output_id(),
+          [fade_audio_opt()]
+        ) :: t()
+  def fade_audio(timeline, cue_id, at, output_id, opts) do
+    from = volumes(Keyword.fetch!(opts, :from))
+    to = volumes(Keyword.fetch!(opts, :to))
+    duration = Keyword.fetch!(opts, :duration)
+    steps = Keyword.get(opts, :steps, 10)
+    input_ids = Enum.uniq(Map.keys(from) ++ Map.keys(to))
+
+    Enum.reduce(1..steps, timeline, fn step, timeline ->
+      progress = step / steps
+
+      inputs =
+        input_ids
+        |> Enum.map(fn input_id ->
+          from_volume = Map.get(from, input_id, 0.0)
+          to_volume = Map.get(to, input_id, 0.0)
+          %{input_id: input_id, volume: from_volume + (to_volume - from_volume) * progress}
+        end)
+        |> Enum.reject(&(step == steps and &1.volume == 0))
+
+      step_at = at + div(duration * step, steps)
+      update_audio(timeline, {:fade, cue_id, step}, step_at, output_id, inputs)
+    end)
+  end
+
+  @doc """
+  Adds a cue that unregisters the input at `at`.
+  """
+  @spec drop_input(t(), cue_id(), Membrane.Time.t(), LiveCompositor.input_id()) :: t()
+  def drop_input(timeline, cue_id, at, input_id) do
+    add_cue(timeline, cue_id, at, %Request.UnregisterInput{input_id: input_id})
+  end
+
+  @doc """
+  Adds a cue that unregisters the output at `at`.
+  """
+  @spec drop_output(t(), cue_id(), Membrane.Time.t(), LiveCompositor.output_id()) :: t()
+  def drop_output(timeline, cue_id, at, output_id) do
+    add_cue(timeline, cue_id, at, %Request.UnregisterOutput{output_id: output_id})
+  end
+
+  @doc false
+  @spec cue_request?(any()) :: boolean()
+  def cue_request?(%module{}), do: module in @cue_requests
+  def cue_request?(_request), do: false
+
+  @doc false
+  @spec cue_matches?(cue_id(), [cue_id()]) :: boolean()
+  def cue_matches?(cue_id, cue_ids) do
+    case cue_id do
+      {:fade, fade_id, step} when is_integer(step) -> cue_id in cue_ids or fade_id in cue_ids
+      cue_id -> cue_id in cue_ids
+    end
+  end
+
+  defp volumes(inputs) do
+    Map.new(inputs, fn input -> {input.input_id, Map.get(input, :volume) || 1.0} end)
+  end
+end
@@ -0,0 +1,47 @@
+defmodule Membrane.LiveCompositor.UdpSource do
+  @moduledoc false
+  # Receives packets on a UDP socket opened by the bin. The port is sent to the LiveCompositor
+  # server when the output is registered, so the socket is bound before the registration and
+  # kept open until the source takes it over.
+  #
+  # Once playing, the source notifies the bin with `{:udp_socket_owner, pid}`. The bin makes
+  # it the controlling process of the socket and replies with `:socket_ready`. Packets received
+  # in the meantime wait in the socket.
+
+  use Membrane.Source
+
+  alias Membrane.{Buffer, RemoteStream}
+
+  def_options socket: [
+                spec: :gen_udp.socket()
+              ]
+
+  def_output_pad :output,
+    accepted_format: %RemoteStream{type: :packetized},
+    flow_control: :push
+
+  @impl true
+  def handle_init(_ctx, opt) do
+    {[], %{socket: opt.socket}}
+  end
+
+  @impl true
+  def handle_playing(_ctx, state) do
+    {[
+       stream_format: {:output, %RemoteStream{type: :packetized}},
+       notify_parent: {:udp_socket_owner, self()}
+     ], state}
+  end
+
+  @impl true
+  def handle_parent_notification(:socket_ready, _ctx, state) do
+    :ok = :inet.setopts(state.socket, active: true)
+    {[], state}
+  end
+
+  @impl true
+  def handle_info({:udp, socket, _address, _port, payload}, _ctx, state = %{socket: socket}) do
+    buffer = %Buffer{payload: payload, metadata: %{arrival_ts: Membrane.Time.vm_time()}}
+    {[buffer: {:output, buffer}], state}
+  end
+end
@@ -28,7 +28,8 @@ defmodule Membrane.LiveCompositor.Mixfile do
 
   def application do
     [
-      extra_applications: []
+      mod: {Membrane.LiveCompositor.Application, []},
+      extra_applications: [:crypto]
     ]
   end
 
@@ -45,15 +46,23 @@ defmodule Membrane.LiveCompositor.Mixfile do
       {:membrane_core, "~> 1.0"},
       {:membrane_raw_video_format, "~> 0.3.0 or ~> 0.4.0"},
       {:membrane_opus_plugin, "~> 0.20.4"},
+      {:membrane_h26x_plugin, "~> 0.10.0"},
+      {:membrane_vp8_format, "~> 0.4.0"},
+      {:membrane_vp9_format, "~> 0.4.0"},
+      {:membrane_h264_ffmpeg_plugin, "~> 0.32.0", optional: true},
       ## RTP
       {:membrane_rtp_plugin, "~> 0.30.0"},
       {:membrane_rtp_h264_plugin, "~> 0.20.0"},
+      {:membrane_rtp_vp8_plugin, "~> 0.9.1"},
       {:membrane_tcp_plugin, "~> 0.6.0"},
+      {:membrane_udp_plugin, "~> 0.14.0"},
       {:membrane_rtp_opus_plugin, "~> 0.10.0"},
       # VC server start
       {:muontrap, "~> 1.0"},
       # VC API
       {:req, "~> 0.4.0"},
+      {:finch, "~> 0.19"},
+      {:telemetry, "~> 1.0"},
       {:websockex, "~> 0.4.3"},
       {:jason, "~> 1.4"},
       # Dev
@@ -95,13 +104,20 @@ defmodule Membrane.LiveCompositor.Mixfile do
       extras: ["README.md", "LICENSE"],
       formatters: ["html"],
       source_ref: "v#{@version}",
-      nest_modules_by_prefix: [Membrane.LiveCompositor, Membrane.LiveCompositor.Request],
+      nest_modules_by_prefix: [
+        Membrane.LiveCompositor,
+        Membrane.LiveCompositor.Request,
+        Membrane.LiveCompositor.Scene
+      ],
       groups_for_modules: [
         Encoders: [
           ~r/^Membrane\.LiveCompositor\.Encoder($|\.)/
         ],
         Requests: [
           ~r/^Membrane\.LiveCompositor\.Request($|\.)/
+        ],
+        Scene: [
+          ~r/^Membrane\.LiveCompositor\.Scene($|\.)/
         ]
       ]
     ]
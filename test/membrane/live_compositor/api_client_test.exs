@@ -0,0 +1,72 @@
+defmodule Membrane.LiveCompositor.ApiClientTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{ApiClient, Error, FakeServer}
+
+  @request {:post, "/api/input/input_0/unregister", %{schedule_time_ms: nil}}
+
+  setup do
+    %{lc_address: FakeServer.start_link()}
+  end
+
+  test "emits the duration of every request", %{lc_address: lc_address} do
+    handler_id = make_ref()
+
+    :ok =
+      :telemetry.attach(
+        handler_id,
+        [:membrane_live_compositor, :api_request, :stop],
+        &__MODULE__.handle_telemetry_event/4,
+        {self(), lc_address}
+      )
+
+    on_exit(fn -> :telemetry.detach(handler_id) end)
+
+    assert {:ok, %{}} = ApiClient.send_request(@request, lc_address)
+
+    assert_receive {:telemetry_event, %{duration: duration},
+                    %{method: :post, route: "/api/input/input_0/unregister", result: :ok}}
+
+    assert duration > 0
+  end
+
+  test "emits failed requests" do
+    handler_id = make_ref()
+    lc_address = FakeServer.start_link(fn _method, _path, _body -> {500, %{}} end)
+
+    :ok =
+      :telemetry.attach(
+        handler_id,
+        [:membrane_live_compositor, :api_request, :stop],
+        &__MODULE__.handle_telemetry_event/4,
+        {self(), lc_address}
+      )
+
+    on_exit(fn -> :telemetry.detach(handler_id) end)
+
+    assert {:error, %Error{http_status: 500}} = ApiClient.send_request(@request, lc_address)
+    assert_receive {:telemetry_event, _measurements, %{result: :error}}
+  end
+
+  test "reuses connections of the shared pool", %{lc_address: lc_address} do
+    base_url = ApiClient.base_url(lc_address)
+
+    for _i <- 1..3 do
+      assert {:ok, %{}} = ApiClient.send_request(@request, lc_address, base_url: base_url)
+    end
+
+    assert_received :fake_server_connection
+    refute_received :fake_server_connection
+  end
+
+  @doc false
+  @spec handle_telemetry_event([atom()], map(), map(), {pid(), term()}) :: :ok
+  def handle_telemetry_event(_event, measurements, metadata, {test_process, lc_address}) do
+    # Handlers are global, events of requests sent by other tests are skipped
+    if metadata.lc_address == lc_address do
+      send(test_process, {:telemetry_event, measurements, metadata})
+    end
+
+    :ok
+  end
+end
@@ -0,0 +1,77 @@
+defmodule Membrane.LiveCompositor.ContextTest do
+  use ExUnit.Case, async: true
+
+  require Membrane.Pad
+
+  alias Membrane.LiveCompositor.Context
+  alias Membrane.Pad
+
+  test "add_stream/2 keeps inputs in insertion order" do
+    context =
+      %Context{}
+      |> then(&Context.add_stream(Pad.ref(:video_input, "input_0"), &1))
+      |> then(&Context.add_stream(Pad.ref(:video_input, "input_1"), &1))
+      |> then(&Context.add_stream(Pad.ref(:audio_input, "input_2"), &1))
+
+    assert context.video_inputs == ["input_0", "input_1"]
+    assert context.audio_inputs == ["input_2"]
+  end
+
+  test "add_stream/2 rejects duplicate ids" do
+    context = Context.add_stream(Pad.ref(:video_input, "input_0"), %Context{})
+
+    assert_raise RuntimeError, fn ->
+      Context.add_stream(Pad.ref(:audio_input, "input_0"), context)
+    end
+
+    context = Context.add_stream(Pad.ref(:video_output, "output_0"), context)
+
+    assert_raise RuntimeError, fn ->
+      Context.add_stream(Pad.ref(:audio_output, "output_0"), context)
+    end
+  end
+
+  test "tracks MP4 inputs separately from pads" do
+    context =
+      %Context{}
+      |> then(&Context.add_stream(Pad.ref(:video_input, "input_0"), &1))
+      |> then(&Context.add_mp4_input("mp4_input", &1))
+
+    assert %Context{video_inputs: ["input_0"], mp4_inputs: ["mp4_input"]} = context
+    assert Context.input_registered?("mp4_input", context)
+
+    assert_raise RuntimeError, fn -> Context.add_mp4_input("input_0", context) end
+
+    assert_raise RuntimeError, fn ->
+      Context.add_stream(Pad.ref(:audio_input, "mp4_input"), context)
+    end
+
+    context = Context.remove_input("mp4_input", context)
+
+    assert context.mp4_inputs == []
+    refute Context.input_registered?("mp4_input", context)
+  end
+
+  test "tracks registered renderers" do
+    context =
+      %Context{}
+      |> then(&Context.add_renderer({:shader, "shader"}, &1))
+      |> then(&Context.add_renderer({:image, "image"}, &1))
+      |> then(&Context.add_renderer({:web_renderer, "web_renderer"}, &1))
+
+    assert %Context{shaders: ["shader"], images: ["image"], web_renderers: ["web_renderer"]} =
+             context
+    assert Context.renderer_registered?({:shader, "shader"}, context)
+    assert Context.renderer_registered?({:image, "image"}, context)
+    assert Context.renderer_registered?({:web_renderer, "web_renderer"}, context)
+    refute Context.renderer_registered?({:shader, "image"}, context)
+
+    context =
+      context
+      |> then(&Context.remove_renderer({:shader, "shader"}, &1))
+      |> then(&Context.remove_renderer({:web_renderer, "web_renderer"}, &1))
+
+    assert %Context{shaders: [], images: ["image"], web_renderers: []} = context
+    refute Context.renderer_registered?({:shader, "shader"}, context)
+  end
+end
@@ -0,0 +1,54 @@
+defmodule Membrane.LiveCompositor.ErrorTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.Error
+
+  describe "from_response/1" do
+    test "converts documented error codes to atoms" do
+      response = %Req.Response{
+        status: 400,
+        body: %{
+          "error_code" => "INPUT_STREAM_NOT_FOUND",
+          "message" => "Input not found",
+          "stack" => ["Input not found", "No input with id \"input_0\""]
+        }
+      }
+
+      assert Error.from_response(response) == %Error{
+               error_code: :input_stream_not_found,
+               message: "Input not found",
+               stack: ["Input not found", "No input with id \"input_0\""],
+               http_status: 400
+             }
+    end
+
+    test "keeps unknown error codes as strings" do
+      response = %Req.Response{status: 500, body: %{"error_code" => "NEW_ERROR_CODE"}}
+
+      assert Error.from_response(response) == %Error{
+               error_code: "NEW_ERROR_CODE",
+               stack: [],
+               http_status: 500
+             }
+    end
+
+    test "handles responses without an error code" do
+      response = %Req.Response{status: 502, body: "Bad Gateway"}
+
+      assert Error.from_response(response) == %Error{
+               message: inspect("Bad Gateway"),
+               http_status: 502
+             }
+    end
+  end
+
+  test "from_exception/1 returns a transport error" do
+    exception = %Mint.TransportError{reason: :econnrefused}
+
+    assert Error.from_exception(exception) == %Error{
+             error_code: :transport_error,
+             message: Exception.message(exception),
+             reason: :econnrefused
+           }
+  end
+end
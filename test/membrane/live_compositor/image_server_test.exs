@@ -0,0 +1,32 @@
+defmodule Membrane.LiveCompositor.ImageServerTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.ImageServer
+
+  setup do
+    # Image server connects to the compositor to find out which interface it should listen on
+    {:ok, lc_socket} = :gen_tcp.listen(0, [:binary, ip: {127, 0, 0, 1}])
+    {:ok, lc_port} = :inet.port(lc_socket)
+    {:ok, server} = ImageServer.start_link({{127, 0, 0, 1}, lc_port})
+    %{server: server}
+  end
+
+  test "serves images under the returned URL", %{server: server} do
+    url = ImageServer.put(server, "image", <<0, 1, 2, 3>>)
+
+    assert "http://127.0.0.1:" <> _rest = url
+    assert %Req.Response{status: 200, body: <<0, 1, 2, 3>>} = Req.get!(url, retry: false)
+  end
+
+  test "doesn't serve deleted or replaced images", %{server: server} do
+    deleted_url = ImageServer.put(server, "deleted_image", "data")
+    :ok = ImageServer.delete(server, "deleted_image")
+
+    replaced_url = ImageServer.put(server, "replaced_image", "old data")
+    url = ImageServer.put(server, "replaced_image", "new data")
+
+    assert %Req.Response{status: 404} = Req.get!(deleted_url, retry: false)
+    assert %Req.Response{status: 404} = Req.get!(replaced_url, retry: false)
+    assert %Req.Response{status: 200, body: "new data"} = Req.get!(url, retry: false)
+  end
+end
@@ -0,0 +1,38 @@
+defmodule Membrane.LiveCompositor.InputProcessorTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.VideoInputProcessor
+
+  setup do
+    {[], state} =
+      VideoInputProcessor.handle_init(%{}, %VideoInputProcessor{input_id: "input_0"})
+
+    %{state: state}
+  end
+
+  test "forwards encoded stream formats", %{state: state} do
+    stream_format = %Membrane.VP8{width: 1280, height: 720}
+
+    assert {[stream_format: {:output, ^stream_format}], _state} =
+             VideoInputProcessor.handle_stream_format(:input, stream_format, %{}, state)
+  end
+
+  test "raises on raw video", %{state: state} do
+    stream_format = %Membrane.RawVideo{
+      width: 1280,
+      height: 720,
+      pixel_format: :I420,
+      framerate: {30, 1},
+      aligned: true
+    }
+
+    assert_raise RuntimeError, ~r/`raw_video_encoder` option of the pad is not set/, fn ->
+      VideoInputProcessor.handle_stream_format(:input, stream_format, %{}, state)
+    end
+  end
+
+  test "requests a keyframe upstream when notified by the bin", %{state: state} do
+    assert {[event: {:input, %Membrane.KeyframeRequestEvent{}}], _state} =
+             VideoInputProcessor.handle_parent_notification(:keyframe_request, %{}, state)
+  end
+end
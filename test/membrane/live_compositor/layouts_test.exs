@@ -0,0 +1,128 @@
+defmodule Membrane.LiveCompositor.LayoutsTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{Layouts, Scene}
+
+  @resolution %{width: 1280, height: 720}
+
+  describe "grid/3" do
+    test "returns an empty view for an empty input list" do
+      assert Layouts.grid([], @resolution) == %Scene.View{children: []}
+    end
+
+    test "arranges inputs on a square grid" do
+      %Scene.View{children: children} =
+        Layouts.grid(["input_0", "input_1", "input_2"], @resolution, margin: 10)
+
+      assert positions(children) == [
+               {"layout_input_0", 10, 10, 625, 345},
+               {"layout_input_1", 10, 645, 625, 345},
+               {"layout_input_2", 365, 10, 625, 345}
+             ]
+    end
+  end
+
+  describe "picture_in_picture/3" do
+    test "returns an empty view for an empty input list" do
+      assert Layouts.picture_in_picture([], @resolution) == %Scene.View{children: []}
+    end
+
+    test "renders the first input on the whole output" do
+      %Scene.View{children: [main]} = Layouts.picture_in_picture(["input_0"], @resolution)
+
+      assert positions([main]) == [{"layout_input_0", 0, 0, 1280, 720}]
+    end
+
+    test "positions smaller inputs in every corner with top and left" do
+      input_ids = ["input_0", "input_1", "input_2"]
+
+      expected = %{
+        top_left: [{20, 20}, {220, 20}],
+        top_right: [{20, 940}, {220, 940}],
+        bottom_left: [{520, 20}, {320, 20}],
+        bottom_right: [{520, 940}, {320, 940}]
+      }
+
+      for {corner, expected_positions} <- expected do
+        %Scene.View{children: [_main | pips]} =
+          Layouts.picture_in_picture(input_ids, @resolution, corner: corner)
+
+        assert Enum.all?(pips, &(&1.bottom == nil and &1.right == nil))
+        assert Enum.map(pips, &{&1.top, &1.left}) == expected_positions
+        assert Enum.all?(pips, &(&1.width == 320 and &1.height == 180))
+      end
+    end
+  end
+
+  describe "speaker_focus/3" do
+    test "returns an empty view for an empty input list" do
+      assert Layouts.speaker_focus([], @resolution) == %Scene.View{children: []}
+    end
+
+    test "renders a single input on the whole output" do
+      assert Layouts.speaker_focus(["input_0"], @resolution) ==
+               Layouts.picture_in_picture(["input_0"], @resolution)
+    end
+
+    test "centers the filmstrip below the speaker" do
+      %Scene.View{children: children} =
+        Layouts.speaker_focus(["input_0", "input_1"], @resolution)
+
+      assert positions(children) == [
+               {"layout_input_0", 0, 0, 1280, 576},
+               {"layout_input_1", 576, 512, 256, 144}
+             ]
+    end
+  end
+
+  describe "side_by_side/3 and stacked/3" do
+    test "return an empty view for an empty input list" do
+      assert Layouts.side_by_side([], @resolution) == %Scene.View{children: []}
+      assert Layouts.stacked([], @resolution) == %Scene.View{children: []}
+    end
+
+    test "split the output between inputs" do
+      %Scene.View{children: children} =
+        Layouts.side_by_side(["input_0", "input_1"], @resolution, margin: 10)
+
+      assert positions(children) == [
+               {"layout_input_0", 0, 0, 635, 720},
+               {"layout_input_1", 0, 645, 635, 720}
+             ]
+
+      %Scene.View{children: children} =
+        Layouts.stacked(["input_0", "input_1"], @resolution, margin: 10)
+
+      assert positions(children) == [
+               {"layout_input_0", 0, 0, 1280, 355},
+               {"layout_input_1", 365, 0, 1280, 355}
+             ]
+    end
+  end
+
+  test "ids of inputs don't depend on the layout" do
+    opts = [id_prefix: "output_0", transition: %{duration_ms: 500}, mode: :fill]
+    input_ids = ["input_0", "input_1"]
+
+    layouts = [
+      Layouts.grid(input_ids, @resolution, opts),
+      Layouts.picture_in_picture(input_ids, @resolution, opts),
+      Layouts.speaker_focus(input_ids, @resolution, opts),
+      Layouts.side_by_side(input_ids, @resolution, opts),
+      Layouts.stacked(input_ids, @resolution, opts)
+    ]
+
+    for %Scene.View{children: children} <- layouts do
+      assert Enum.map(children, & &1.id) == ["output_0_input_0", "output_0_input_1"]
+      assert Enum.map(children, & &1.child.input_id) == input_ids
+      assert Enum.all?(children, &(&1.transition == %{duration_ms: 500} and &1.mode == :fill))
+    end
+  end
+
+  defp positions(rescalers) do
+    Enum.map(rescalers, fn rescaler = %Scene.Rescaler{} ->
+      assert rescaler.bottom == nil and rescaler.right == nil
+      {rescaler.id, rescaler.top, rescaler.left, rescaler.width, rescaler.height}
+    end)
+  end
+end
@@ -0,0 +1,556 @@
+defmodule Membrane.LiveCompositor.LiveCompositorTest do
+  use ExUnit.Case, async: true
+
+  require Membrane.Pad
+
+  alias Membrane.{ChildrenSpec, LiveCompositor, Pad}
+  alias Membrane.LiveCompositor.{Context, Error, FakeServer, Request, Scene, State, Timeline}
+
+  @request %Request.UnregisterInput{input_id: "input_0"}
+
+  @video_input_options %{
+    transport: :tcp,
+    port: 9000,
+    required: false,
+    offset: nil,
+    raw_video_encoder: nil,
+    video_codec: :h264
+  }
+
+  describe "request/3" do
+    test "returns the result of a request handled by the bin" do
+      bin =
+        spawn(fn ->
+          receive do
+            {:live_compositor_request, from, ref, @request} ->
+              send(from, {ref, {:ok, %{"result" => "ok"}}})
+          end
+        end)
+
+      assert LiveCompositor.request(bin, @request) == {:ok, %{"result" => "ok"}}
+    end
+
+    test "returns an error when the bin goes down or doesn't reply in time" do
+      bin =
+        spawn(fn ->
+          receive do
+            {:live_compositor_request, _from, _ref, _request} -> exit(:crashed)
+          end
+        end)
+
+      assert LiveCompositor.request(bin, @request) ==
+               {:error, %Error{error_code: :transport_error, reason: {:bin_down, :crashed}}}
+
+      bin = spawn(fn -> Process.sleep(:infinity) end)
+
+      assert LiveCompositor.request(bin, @request, 10) ==
+               {:error, %Error{error_code: :transport_error, reason: :timeout}}
+
+      Process.exit(bin, :kill)
+    end
+
+    test "drops results that arrive after the timeout" do
+      bin =
+        spawn(fn ->
+          receive do
+            {:live_compositor_request, from, ref, _request} ->
+              Process.sleep(50)
+              send(from, {ref, {:ok, %{}}})
+          end
+        end)
+
+      assert {:error, %Error{reason: :timeout}} = LiveCompositor.request(bin, @request, 10)
+      Process.sleep(100)
+      refute_received {_ref, {:ok, %{}}}
+    end
+
+    test "sends the request directly to the server" do
+      lc_address = FakeServer.start_link(fn _method, _path, _body -> {200, %{"ok" => true}} end)
+
+      assert LiveCompositor.request(lc_address, @request) == {:ok, %{"ok" => true}}
+      assert_receive {:fake_server_request, :POST, "/api/input/input_0/unregister", _body}
+    end
+
+    test "returns errors reported by the server" do
+      lc_address =
+        FakeServer.start_link(fn _method, _path, _body ->
+          {400, %{"error_code" => "INPUT_STREAM_NOT_FOUND", "message" => "Input not found"}}
+        end)
+
+      assert {:error, %Error{error_code: :input_stream_not_found, http_status: 400}} =
+               LiveCompositor.request(lc_address, @request)
+    end
+
+    test "rejects requests that can only be handled by the bin" do
+      lc_address = FakeServer.start_link()
+
+      requests = [
+        %Request.UpdateOutputs{updates: []},
+        %Request.CancelTimeline{timeline_id: "timeline"},
+        %Request.RegisterImage{image_id: "image", asset_type: :png, data: <<0>>}
+      ]
+
+      for request <- requests do
+        assert LiveCompositor.request(lc_address, request) == {:error, :bin_required}
+      end
+
+      refute_received :fake_server_connection
+    end
+  end
+
+  describe "handle_parent_notification/3" do
+    setup do
+      %{state: state(FakeServer.start_link())}
+    end
+
+    test "generates refs for requests without one", %{state: state} do
+      request = %Request.UpdateVideoOutput{output_id: "output_0", root: %Scene.View{}}
+
+      assert {[notify_parent: {:request_result, sent_request, {:ok, _body}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      assert %Request.UpdateVideoOutput{sent_request | ref: nil} == request
+      assert is_reference(sent_request.ref)
+    end
+
+    test "keeps refs set by the parent", %{state: state} do
+      request = %Request.UpdateVideoOutput{output_id: "output_0", root: %Scene.View{}, ref: 1}
+
+      assert {[notify_parent: {:request_result, ^request, {:ok, _body}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+    end
+  end
+
+  describe "handle_info/3" do
+    test "replies to unsupported requests right away" do
+      state = state(FakeServer.start_link())
+      message = {:live_compositor_request, self(), 1, %{output_id: "output_0"}}
+
+      assert {[], _state} = LiveCompositor.handle_info(message, %{}, state)
+      assert_received {1, {:error, %Error{reason: :unsupported_request}}}
+      refute_received {:fake_server_request, _method, _path, _body}
+    end
+  end
+
+  describe "handle_child_notification/4" do
+    test "sends keyframe requests of encoded and raw video outputs to the server" do
+      state = state(FakeServer.start_link())
+
+      for processor <- [:output_processor, :raw_output_processor] do
+        assert {[], _state} =
+                 LiveCompositor.handle_child_notification(
+                   :keyframe_request,
+                   {processor, "output_0"},
+                   %{},
+                   state
+                 )
+
+        assert_receive {:fake_server_request, :POST, "/api/output/output_0/request_keyframe",
+                        _body}
+      end
+    end
+  end
+
+  describe "UpdateOutputs" do
+    setup do
+      lc_address = FakeServer.start_link()
+      state = %State{state(lc_address) | composing_started_at: Membrane.Time.monotonic_time()}
+      %{state: state}
+    end
+
+    test "schedules all updates at the same time", %{state: state} do
+      request = %Request.UpdateOutputs{
+        updates: [
+          %Request.UpdateVideoOutput{output_id: "output_0", root: %Scene.View{}},
+          %Request.UpdateAudioOutput{output_id: "output_1", inputs: []}
+        ]
+      }
+
+      assert {[notify_parent: {:request_result, _request, {:ok, [_video, _audio]}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      assert_receive {:fake_server_request, :POST, "/api/output/output_0/update",
+                      %{"schedule_time_ms" => schedule_time_ms}}
+
+      assert_receive {:fake_server_request, :POST, "/api/output/output_1/update",
+                      %{"schedule_time_ms" => ^schedule_time_ms}}
+
+      assert is_integer(schedule_time_ms)
+    end
+
+    test "doesn't send any update when one of the scenes is invalid", %{state: state} do
+      request = %Request.UpdateOutputs{
+        updates: [
+          %Request.UpdateAudioOutput{output_id: "output_1", inputs: []},
+          %Request.UpdateVideoOutput{
+            output_id: "output_0",
+            root: %Scene.InputStream{input_id: "unknown_input"}
+          }
+        ]
+      }
+
+      assert {[notify_parent: {:request_result, _request, {:error, error}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      assert error == {:invalid_scene, [{:unknown_input, "unknown_input"}]}
+      refute_received {:fake_server_request, _method, _path, _body}
+    end
+
+    test "returns the result of every update when one of them fails" do
+      lc_address =
+        FakeServer.start_link(fn
+          :POST, "/api/output/output_1/update", _body -> {400, %{"error_code" => "ERROR"}}
+          _method, _path, _body -> {200, %{}}
+        end)
+
+      state = %State{state(lc_address) | composing_started_at: Membrane.Time.monotonic_time()}
+
+      request = %Request.UpdateOutputs{
+        updates: [
+          %Request.UpdateVideoOutput{output_id: "output_0", root: %Scene.View{}},
+          %Request.UpdateAudioOutput{output_id: "output_1", inputs: []}
+        ]
+      }
+
+      assert {[notify_parent: {:request_result, _request, {:error, error}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      assert {:update_outputs_failed, [{:ok, %{}}, {:error, %Error{http_status: 400}}]} = error
+    end
+
+    test "schedules updates at the start when the composing was not started", %{state: state} do
+      state = %State{state | composing_started_at: nil}
+
+      request = %Request.UpdateOutputs{
+        updates: [%Request.UpdateAudioOutput{output_id: "output_1", inputs: []}]
+      }
+
+      assert {[notify_parent: {:request_result, _request, {:ok, [_audio]}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      assert_receive {:fake_server_request, :POST, "/api/output/output_1/update",
+                      %{"schedule_time_ms" => 0}}
+    end
+
+    test "schedules updates after the last output PTS in offline processing", %{state: state} do
+      state = %State{state | composing_strategy: :offline_processing}
+
+      {[], state} =
+        LiveCompositor.handle_child_notification(
+          {:pts, Membrane.Time.seconds(10)},
+          {:output_processor, "output_0"},
+          %{},
+          state
+        )
+
+      request = %Request.UpdateOutputs{
+        updates: [%Request.UpdateAudioOutput{output_id: "output_1", inputs: []}],
+        delay: Membrane.Time.milliseconds(500)
+      }
+
+      assert {[notify_parent: {:request_result, _request, {:ok, [_audio]}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      assert_receive {:fake_server_request, :POST, "/api/output/output_1/update",
+                      %{"schedule_time_ms" => 10_500}}
+    end
+
+    test "rejects updates scheduled at a time that has already passed", %{state: state} do
+      started_at = state.composing_started_at - Membrane.Time.seconds(1)
+      state = %State{state | composing_started_at: started_at}
+
+      request = %Request.UpdateOutputs{
+        updates: [%Request.UpdateAudioOutput{output_id: "output_1", inputs: []}],
+        schedule_time: Membrane.Time.milliseconds(500)
+      }
+
+      assert {[notify_parent: {:request_result, _request, {:error, error}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      assert error == {:schedule_time_passed, Membrane.Time.milliseconds(500)}
+      refute_received {:fake_server_request, _method, _path, _body}
+    end
+
+    test "returns an error when updates reach the server after the schedule time" do
+      lc_address =
+        FakeServer.start_link(fn _method, _path, _body ->
+          Process.sleep(200)
+          {200, %{}}
+        end)
+
+      state = %State{state(lc_address) | composing_started_at: Membrane.Time.monotonic_time()}
+
+      request = %Request.UpdateOutputs{
+        updates: [%Request.UpdateAudioOutput{output_id: "output_1", inputs: []}],
+        delay: Membrane.Time.milliseconds(50)
+      }
+
+      assert {[notify_parent: {:request_result, _request, {:error, error}}], _state} =
+               LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      assert {:schedule_time_missed, [{:ok, %{}}]} = error
+    end
+  end
+
+  describe "RegisterMp4Input" do
+    # With the server started by the bin, the input isn't guarded by the resource guard.
+    @ctx %{resource_guard: nil}
+
+    test "notifies about registration of the tracks reported by the server" do
+      lc_address =
+        FakeServer.start_link(fn _method, _path, _body ->
+          {200, %{"video_duration_ms" => 1000, "audio_duration_ms" => nil}}
+        end)
+
+      state = %State{state(lc_address) | server_pid: self()}
+      request = %Request.RegisterMp4Input{input_id: "mp4_input", path: "video.mp4"}
+
+      assert {[
+                notify_parent: {:request_result, _request, {:ok, _body}},
+                notify_parent: {:input_registered, Pad.ref(:video_input, "mp4_input"), _context}
+              ], _state} = LiveCompositor.handle_parent_notification(request, @ctx, state)
+    end
+
+    test "notifies about the tracks declared in the request if the server doesn't report them" do
+      state = %State{state(FakeServer.start_link()) | server_pid: self()}
+
+      request = %Request.RegisterMp4Input{
+        input_id: "mp4_input",
+        path: "audio.mp4",
+        tracks: [:audio]
+      }
+
+      assert {[
+                notify_parent: {:request_result, _request, {:ok, _body}},
+                notify_parent: {:input_registered, Pad.ref(:audio_input, "mp4_input"), _context}
+              ], _state} = LiveCompositor.handle_parent_notification(request, @ctx, state)
+    end
+  end
+
+  describe "timelines" do
+    test "scene of a scheduled cue becomes current once its time has passed" do
+      state = %State{
+        state(FakeServer.start_link())
+        | composing_started_at: Membrane.Time.monotonic_time(),
+          video_scenes: %{"output_0" => %Scene.View{id: "current"}}
+      }
+
+      next_root = %Scene.View{id: "next"}
+
+      timeline =
+        Timeline.new("timeline", start: :now)
+        |> Timeline.add_cue(
+          "cue",
+          Membrane.Time.milliseconds(200),
+          %Request.UpdateVideoOutput{output_id: "output_0", root: next_root}
+        )
+
+      {_actions, state} =
+        LiveCompositor.handle_parent_notification(
+          %Request.SubmitTimeline{timeline: timeline},
+          %{},
+          state
+        )
+
+      assert_received {:timeline_tick, "timeline"}
+      {[], state} = LiveCompositor.handle_info({:timeline_tick, "timeline"}, %{}, state)
+
+      assert_received {:fake_server_request, :POST, "/api/output/output_0/update",
+                       %{"schedule_time_ms" => schedule_time_ms}}
+
+      assert schedule_time_ms >= 200
+      assert state.video_scenes["output_0"] == %Scene.View{id: "current"}
+      assert [{_schedule_time, ^next_root}] = state.pending_updates[{:video_scenes, "output_0"}]
+
+      Process.sleep(250)
+      assert_received {:timeline_tick, "timeline"}
+
+      assert {[notify_parent: {:timeline_cue_fired, "timeline", "cue"}], state} =
+               LiveCompositor.handle_info({:timeline_tick, "timeline"}, %{}, state)
+
+      assert state.video_scenes["output_0"] == next_root
+      assert state.pending_updates == %{}
+    end
+  end
+
+  describe "before the setup is completed" do
+    setup do
+      %{state: %State{state(FakeServer.start_link()) | setup_completed?: false}}
+    end
+
+    test "requests are handled in order once the bin connects to the server", %{state: state} do
+      first = %Request.UpdateVideoOutput{output_id: "output_0", root: %Scene.View{}, ref: 1}
+      second = %Request.UpdateVideoOutput{output_id: "output_1", root: %Scene.View{}, ref: 2}
+
+      assert {[], state} = LiveCompositor.handle_parent_notification(first, %{}, state)
+      assert {[], state} = LiveCompositor.handle_parent_notification(:start_composing, %{}, state)
+
+      message = {:live_compositor_request, self(), 3, second}
+      assert {[], state} = LiveCompositor.handle_info(message, %{}, state)
+
+      refute_received {:fake_server_request, _method, _path, _body}
+
+      assert {[setup: :complete, notify_parent: {:request_result, ^first, {:ok, _body}}], state} =
+               LiveCompositor.handle_info(:websocket_connected, %{}, state)
+
+      assert state.setup_completed?
+      assert state.composing_started_at != nil
+      assert_received {:fake_server_request, :POST, "/api/output/output_0/update", _body}
+      assert_received {:fake_server_request, :POST, "/api/start", _body}
+      assert_received {:fake_server_request, :POST, "/api/output/output_1/update", _body}
+      assert_received {3, {:ok, _body}}
+    end
+  end
+
+  describe "handle_pad_added/3" do
+    setup do
+      lc_address = FakeServer.start_link(fn _method, _path, _body -> {200, %{"port" => 9000}} end)
+      %{state: state(lc_address)}
+    end
+
+    test "encodes raw video inputs with the raw video encoder", %{state: state} do
+      options = %{@video_input_options | raw_video_encoder: Membrane.Debug.Filter}
+      children = add_video_input(options, state)
+
+      assert children[{:raw_video_encoder, "input_0"}] == Membrane.Debug.Filter
+      assert Map.has_key?(children, {:video_input_processor, "input_0"})
+    end
+
+    test "checks that inputs without the encoder are encoded", %{state: state} do
+      children = add_video_input(@video_input_options, state)
+
+      refute Map.has_key?(children, {:raw_video_encoder, "input_0"})
+
+      assert children[{:video_input_processor, "input_0"}] ==
+               %Membrane.LiveCompositor.VideoInputProcessor{input_id: "input_0"}
+    end
+
+    test "parses H264 inputs in any alignment and stream structure", %{state: state} do
+      children = add_video_input(@video_input_options, state)
+
+      assert %Membrane.H264.Parser{output_alignment: :nalu, output_stream_structure: :annexb} =
+               children[{:h264_input_parser, "input_0"}]
+
+      children = add_video_input(%{@video_input_options | video_codec: :vp8}, state)
+      refute Map.has_key?(children, {:h264_input_parser, "input_0"})
+    end
+
+    test "payloads inputs according to their codec", %{state: state} do
+      payloaders = [
+        h264: Membrane.RTP.H264.Payloader,
+        vp8: Membrane.RTP.VP8.Payloader,
+        vp9: Membrane.LiveCompositor.RtpVp9Payloader
+      ]
+
+      for {codec, payloader} <- payloaders do
+        options = %{
+          @video_input_options
+          | video_codec: codec,
+            raw_video_encoder: Membrane.Debug.Filter
+        }
+
+        children = add_video_input(options, state)
+
+        assert children[{:rtp_video_payloader, "input_0"}] == payloader
+      end
+    end
+  end
+
+  describe "server failures" do
+    setup do
+      monitor = make_ref()
+      state = %State{state(FakeServer.start_link()) | event_handler_monitor: monitor}
+      %{state: state, monitor: monitor}
+    end
+
+    test "reports exit of the event handler as server down", %{state: state, monitor: monitor} do
+      state = %State{state | on_server_crash: :notify}
+      message = {:DOWN, monitor, :process, self(), :closed}
+
+      assert {[notify_parent: {:server_down, {:event_handler_exited, :closed}}], state} =
+               LiveCompositor.handle_info(message, %{}, state)
+
+      assert state.server_down?
+      assert state.event_handler_monitor == nil
+
+      assert {[], _state} =
+               LiveCompositor.handle_info(
+                 {:live_compositor_server_down, {:server_exited, :killed}},
+                 %{},
+                 state
+               )
+    end
+
+    test "bin terminates when the event handler exits", %{state: state, monitor: monitor} do
+      state = %State{state | on_server_crash: :terminate}
+      message = {:DOWN, monitor, :process, self(), :closed}
+
+      assert {[notify_parent: {:server_down, reason}], _state} =
+               LiveCompositor.handle_info(message, %{}, state)
+
+      assert_received {:terminate_on_server_down, ^reason}
+    end
+
+    test "requests are queued while the server is restarted", %{state: state} do
+      state = %State{state | on_server_crash: :restart, server_down?: true}
+      request = %Request.UpdateVideoOutput{output_id: "output_0", root: %Scene.View{}}
+
+      assert {[], state} = LiveCompositor.handle_parent_notification(request, %{}, state)
+
+      message = {:live_compositor_request, self(), 1, request}
+      assert {[], state} = LiveCompositor.handle_info(message, %{}, state)
+      assert {[], state} = LiveCompositor.handle_info({:timeline_tick, "timeline"}, %{}, state)
+
+      assert [{:info, ^message}, {:notification, ^request}] = state.queued_messages
+      refute_received {:fake_server_request, _method, _path, _body}
+    end
+
+    test "restored video inputs are requested to send a keyframe", %{state: state} do
+      input_ref = Pad.ref(:video_input, "input_0")
+      connection_info = {:connection_info, {127, 0, 0, 1}, 9000}
+
+      assert {[notify_parent: {:input_registered, ^input_ref, _context}], _state} =
+               LiveCompositor.handle_child_notification(
+                 connection_info,
+                 {:tcp_sink, input_ref, 0},
+                 %{},
+                 state
+               )
+
+      assert {[
+                notify_parent: {:input_registered, ^input_ref, _context},
+                notify_child: {{:video_input_processor, "input_0"}, :keyframe_request}
+              ],
+              _state} =
+               LiveCompositor.handle_child_notification(
+                 connection_info,
+                 {:tcp_sink, input_ref, 1},
+                 %{},
+                 state
+               )
+    end
+  end
+
+  defp add_video_input(options, state) do
+    pad_ref = Pad.ref(:video_input, "input_0")
+    {actions, _state} = LiveCompositor.handle_pad_added(pad_ref, %{pad_options: options}, state)
+
+    for {:spec, {links, _spec_options}} <- actions,
+        %ChildrenSpec.Builder{children: children} <- List.wrap(links),
+        {name, definition, _child_options} <- children,
+        into: %{},
+        do: {name, definition}
+  end
+
+  defp state(lc_address) do
+    %State{
+      options: nil,
+      output_framerate: {30, 1},
+      output_sample_rate: 48_000,
+      lc_address: lc_address,
+      context: %Context{video_outputs: ["output_0"]},
+      composing_strategy: :real_time,
+      setup_completed?: true
+    }
+  end
+end
@@ -0,0 +1,113 @@
+defmodule Membrane.LiveCompositor.OutputProcessorTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{AudioOutputProcessor, VideoOutputProcessor}
+
+  @raw_video %Membrane.RawVideo{
+    width: 1280,
+    height: 720,
+    pixel_format: :I420,
+    framerate: {30, 1},
+    aligned: true
+  }
+
+  describe "VideoOutputProcessor" do
+    test "sends the output stream format of raw video" do
+      state = video_processor(@raw_video)
+      decoded_format = %Membrane.RawVideo{@raw_video | framerate: nil}
+
+      assert {[stream_format: {:output, @raw_video}], _state} =
+               VideoOutputProcessor.handle_stream_format(:input, decoded_format, %{}, state)
+    end
+
+    test "keeps H264 stream format from the parser and sets the output framerate" do
+      state =
+        video_processor(%Membrane.H264{
+          width: 1280,
+          height: 720,
+          framerate: {30, 1},
+          alignment: :nalu,
+          stream_structure: :annexb
+        })
+
+      parsed_format = %Membrane.H264{
+        width: 1920,
+        height: 1080,
+        profile: :high,
+        alignment: :nalu,
+        stream_structure: :annexb
+      }
+
+      assert {[stream_format: {:output, output_format}], _state} =
+               VideoOutputProcessor.handle_stream_format(:input, parsed_format, %{}, state)
+
+      assert output_format == %Membrane.H264{parsed_format | framerate: {30, 1}}
+    end
+
+    test "sends the output stream format of VP8" do
+      output_format = %Membrane.VP8{width: 1280, height: 720}
+      state = video_processor(output_format)
+
+      assert {[stream_format: {:output, ^output_format}], _state} =
+               VideoOutputProcessor.handle_stream_format(:input, %Membrane.VP8{}, %{}, state)
+    end
+
+    test "replaces the stream format of VP8 depayloader" do
+      output_format = %Membrane.VP8{width: 1280, height: 720}
+      state = video_processor(output_format)
+      depayloaded_format = %Membrane.RemoteStream{type: :packetized, content_format: Membrane.VP8}
+
+      assert {[stream_format: {:output, ^output_format}], _state} =
+               VideoOutputProcessor.handle_stream_format(:input, depayloaded_format, %{}, state)
+    end
+
+    test "notifies the parent about keyframe requests" do
+      state = video_processor(@raw_video)
+      event = %Membrane.KeyframeRequestEvent{}
+
+      assert {[notify_parent: :keyframe_request], _state} =
+               VideoOutputProcessor.handle_event(:output, event, %{}, state)
+    end
+
+    test "notifies the parent about PTS of buffers only when enabled" do
+      buffer = %Membrane.Buffer{payload: <<>>, pts: Membrane.Time.seconds(1)}
+
+      state = video_processor(@raw_video)
+
+      assert {[buffer: {:output, ^buffer}], _state} =
+               VideoOutputProcessor.handle_buffer(:input, buffer, %{}, state)
+
+      {[], state} =
+        VideoOutputProcessor.handle_init(%{}, %VideoOutputProcessor{
+          output_stream_format: @raw_video,
+          notify_pts?: true
+        })
+
+      assert {[notify_parent: {:pts, pts}, buffer: {:output, ^buffer}], _state} =
+               VideoOutputProcessor.handle_buffer(:input, buffer, %{}, state)
+
+      assert pts == Membrane.Time.seconds(1)
+    end
+  end
+
+  describe "AudioOutputProcessor" do
+    test "sends Opus stream format with the channel count of the encoder" do
+      {[], state} = AudioOutputProcessor.handle_init(%{}, %AudioOutputProcessor{channels: 1})
+      received_format = %Membrane.RemoteStream{type: :packetized, content_format: Membrane.Opus}
+
+      assert {[stream_format: {:output, output_format}], _state} =
+               AudioOutputProcessor.handle_stream_format(:input, received_format, %{}, state)
+
+      assert output_format == %Membrane.Opus{channels: 1, self_delimiting?: false}
+    end
+  end
+
+  defp video_processor(output_stream_format) do
+    {[], state} =
+      VideoOutputProcessor.handle_init(%{}, %VideoOutputProcessor{
+        output_stream_format: output_stream_format
+      })
+
+    state
+  end
+end
@@ -0,0 +1,42 @@
+defmodule Membrane.LiveCompositor.RequestPolicyTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{Request, RequestPolicy}
+
+  @base_delay Membrane.Time.milliseconds(100)
+
+  test "retry_delay/2 grows with the backoff" do
+    linear = %RequestPolicy{backoff: :linear, base_delay: @base_delay}
+    exponential = %RequestPolicy{backoff: :exponential, base_delay: @base_delay}
+    constant = %RequestPolicy{backoff: :constant, base_delay: @base_delay}
+
+    assert Enum.map(1..4, &RequestPolicy.retry_delay(linear, &1)) ==
+             Enum.map([100, 200, 300, 400], &Membrane.Time.milliseconds/1)
+
+    assert Enum.map(1..4, &RequestPolicy.retry_delay(exponential, &1)) ==
+             Enum.map([100, 200, 400, 800], &Membrane.Time.milliseconds/1)
+
+    assert Enum.map(1..4, &RequestPolicy.retry_delay(constant, &1)) ==
+             Enum.map([100, 100, 100, 100], &Membrane.Time.milliseconds/1)
+  end
+
+  test "retry?/2 retries only safe requests by default" do
+    policy = %RequestPolicy{}
+
+    assert RequestPolicy.retry?(policy, %Request.UpdateVideoOutput{output_id: "out", root: nil})
+    assert RequestPolicy.retry?(policy, %Request.KeyframeRequest{output_id: "out"})
+    refute RequestPolicy.retry?(policy, %Request.UnregisterInput{input_id: "in"})
+    refute RequestPolicy.retry?(policy, :internal)
+  end
+
+  test "retry?/2 follows the retry option" do
+    request = %Request.UnregisterInput{input_id: "in"}
+
+    assert RequestPolicy.retry?(%RequestPolicy{retry: :all}, request)
+    assert RequestPolicy.retry?(%RequestPolicy{retry: :all}, :internal)
+    refute RequestPolicy.retry?(%RequestPolicy{retry: :none}, request)
+    assert RequestPolicy.retry?(%RequestPolicy{retry: [Request.UnregisterInput]}, request)
+    refute RequestPolicy.retry?(%RequestPolicy{retry: [Request.UnregisterOutput]}, request)
+    refute RequestPolicy.retry?(%RequestPolicy{retry: [Request.UnregisterInput]}, :internal)
+  end
+end
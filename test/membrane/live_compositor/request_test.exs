@@ -0,0 +1,52 @@
+defmodule Membrane.LiveCompositor.RequestTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{ApiClient, Request}
+
+  test "RegisterWebRenderer is sent with an encoded instance id" do
+    request = %Request.RegisterWebRenderer{
+      instance_id: "web renderer",
+      url: "https://example.com",
+      resolution: %{width: 1280, height: 720},
+      embedding_method: :native_embedding_over_content
+    }
+
+    assert ApiClient.IntoRequest.into_request(request) ==
+             {:post, "/api/web-renderer/web+renderer/register",
+              %{
+                url: "https://example.com",
+                resolution: %{width: 1280, height: 720},
+                embedding_method: :native_embedding_over_content
+              }}
+
+    assert ApiClient.IntoRequest.into_request(%Request.UnregisterWebRenderer{
+             instance_id: "web renderer"
+           }) == {:post, "/api/web-renderer/web+renderer/unregister", %{}}
+  end
+
+  test "RegisterMp4Input is sent as an MP4 input with offset in milliseconds" do
+    request = %Request.RegisterMp4Input{
+      input_id: "mp4_input",
+      url: "https://example.com/video.mp4",
+      loop: true,
+      offset: Membrane.Time.seconds(2)
+    }
+
+    assert ApiClient.IntoRequest.into_request(request) ==
+             {:post, "/api/input/mp4_input/register",
+              %{
+                type: :mp4,
+                url: "https://example.com/video.mp4",
+                path: nil,
+                loop: true,
+                required: false,
+                offset_ms: 2000
+              }}
+
+    assert {:post, _route, %{offset_ms: nil}} =
+             ApiClient.IntoRequest.into_request(%Request.RegisterMp4Input{
+               input_id: "mp4_input",
+               path: "/video.mp4"
+             })
+  end
+end
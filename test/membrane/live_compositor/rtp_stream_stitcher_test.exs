@@ -0,0 +1,81 @@
+defmodule Membrane.LiveCompositor.RtpStreamStitcherTest do
+  use ExUnit.Case, async: true
+
+  require Membrane.Pad
+
+  alias Membrane.{Buffer, Pad, RemoteStream}
+  alias Membrane.LiveCompositor.RtpStreamStitcher
+
+  @clock_rate 90_000
+
+  setup do
+    {[], state} = RtpStreamStitcher.handle_init(%{}, %RtpStreamStitcher{clock_rate: @clock_rate})
+    %{state: state}
+  end
+
+  test "passes the first stream unchanged", %{state: state} do
+    {packets, _state} = stitch(state, 0, [{1, 65_534, 1_000}, {1, 65_535, 4_000}, {1, 0, 7_000}])
+
+    assert packets == [{1, 65_534, 1_000}, {1, 65_535, 4_000}, {1, 0, 7_000}]
+  end
+
+  test "continues sequence numbers across an SSRC switch", %{state: state} do
+    {_packets, state} = stitch(state, 0, [{1, 65_534, 1_000}, {1, 65_535, 4_000}])
+    {packets, _state} = stitch(state, 1, [{2, 100, 50}, {2, 101, 3_050}])
+
+    assert [{1, 0, first_timestamp}, {1, 1, second_timestamp}] = packets
+    assert first_timestamp - 4_000 in 0..@clock_rate
+    assert second_timestamp - first_timestamp == 3_000
+  end
+
+  test "handles wraparound of the new stream", %{state: state} do
+    {_packets, state} = stitch(state, 0, [{1, 10, 1_000}])
+    {packets, _state} = stitch(state, 1, [{2, 65_535, 4_294_967_000}, {2, 0, 2_704}])
+
+    assert [{1, 11, first_timestamp}, {1, 12, second_timestamp}] = packets
+    assert first_timestamp - 1_000 in 0..@clock_rate
+    assert second_timestamp - first_timestamp == 3_000
+  end
+
+  test "drops RTCP packets only after the stream was restarted", %{state: state} do
+    rtcp = %Buffer{payload: <<0x80, 203, 0, 1, 0, 0, 0, 1>>}
+
+    assert {[buffer: {:output, ^rtcp}], state} =
+             RtpStreamStitcher.handle_buffer(Pad.ref(:input, 0), rtcp, %{}, state)
+
+    {_packets, state} = stitch(state, 0, [{1, 10, 1_000}])
+    {_packets, state} = stitch(state, 1, [{2, 10, 1_000}])
+
+    assert {[], _state} = RtpStreamStitcher.handle_buffer(Pad.ref(:input, 1), rtcp, %{}, state)
+  end
+
+  test "forwards only the first stream format", %{state: state} do
+    stream_format = %RemoteStream{type: :packetized}
+
+    assert {[stream_format: {:output, ^stream_format}], state} =
+             RtpStreamStitcher.handle_stream_format(Pad.ref(:input, 0), stream_format, %{}, state)
+
+    assert {[], _state} =
+             RtpStreamStitcher.handle_stream_format(Pad.ref(:input, 1), stream_format, %{}, state)
+  end
+
+  test "sends end of stream only when the parent confirms it", %{state: state} do
+    assert {[notify_parent: {:end_of_stream, Pad.ref(:input, 0)}], state} =
+             RtpStreamStitcher.handle_end_of_stream(Pad.ref(:input, 0), %{}, state)
+
+    assert {[end_of_stream: :output], _state} =
+             RtpStreamStitcher.handle_parent_notification(:end_of_stream, %{}, state)
+  end
+
+  defp stitch(state, generation, packets) do
+    Enum.flat_map_reduce(packets, state, fn {ssrc, seq, timestamp}, state ->
+      buffer = %Buffer{payload: <<0x80, 96, seq::16, timestamp::32, ssrc::32, "payload">>}
+
+      {[buffer: {:output, %Buffer{payload: payload}}], state} =
+        RtpStreamStitcher.handle_buffer(Pad.ref(:input, generation), buffer, %{}, state)
+
+      <<0x80, 96, seq::16, timestamp::32, ssrc::32, "payload">> = payload
+      {[{ssrc, seq, timestamp}], state}
+    end)
+  end
+end
@@ -0,0 +1,42 @@
+defmodule Membrane.LiveCompositor.RtpVp9PayloaderTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.Buffer
+  alias Membrane.LiveCompositor.RtpVp9Payloader
+
+  # Frame marker, profile 0, show_existing_frame = 0 and frame_type
+  @keyframe <<0b10000000, 0::8000>>
+  @inter_frame <<0b10000100, 0::8000>>
+
+  setup do
+    {[], state} = RtpVp9Payloader.handle_init(%{}, %RtpVp9Payloader{max_payload_size: 401})
+    %{state: state}
+  end
+
+  test "splits frames and marks their start and end", %{state: state} do
+    buffer = %Buffer{payload: @keyframe, pts: 1_000, metadata: %{}}
+
+    assert {[buffer: {:output, buffers}], _state} =
+             RtpVp9Payloader.handle_buffer(:input, buffer, %{}, state)
+
+    assert Enum.map(buffers, &descriptor/1) == [0b00001000, 0b00000000, 0b00000100]
+    assert Enum.map(buffers, & &1.metadata.rtp.marker) == [false, false, true]
+    assert Enum.all?(buffers, &(&1.pts == 1_000))
+
+    assert Enum.map_join(buffers, fn %Buffer{payload: <<_descriptor, chunk::binary>>} ->
+             chunk
+           end) == @keyframe
+  end
+
+  test "sets P bit on inter frames", %{state: state} do
+    buffer = %Buffer{payload: binary_part(@inter_frame, 0, 100), metadata: %{}}
+
+    assert {[buffer: {:output, [packet]}], _state} =
+             RtpVp9Payloader.handle_buffer(:input, buffer, %{}, state)
+
+    assert descriptor(packet) == 0b01001100
+    assert packet.metadata.rtp.marker
+  end
+
+  defp descriptor(%Buffer{payload: <<descriptor, _rest::binary>>}), do: descriptor
+end
@@ -0,0 +1,83 @@
+defmodule Membrane.LiveCompositor.SceneTransitionsTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{Scene, SceneTransitions}
+
+  @transition {Membrane.Time.milliseconds(500), nil}
+
+  test "returns the scene unchanged without a transition or a previous scene" do
+    root = rescaler("rescaler", "input_0")
+
+    assert SceneTransitions.inject(root, root, nil) == root
+    assert SceneTransitions.inject(root, nil, @transition) == root
+  end
+
+  test "adds the transition to components with ids from the previous scene" do
+    previous_root = %Scene.View{
+      id: "view",
+      children: [rescaler("rescaler_0", "input_0"), rescaler("rescaler_1", "input_1")]
+    }
+
+    root = %Scene.View{
+      id: "view",
+      children: [
+        %Scene.Tiles{id: "tiles", children: [rescaler("rescaler_1", "input_1")]},
+        rescaler("rescaler_2", "input_2")
+      ]
+    }
+
+    assert %Scene.View{
+             transition: %{duration_ms: 500},
+             children: [
+               %Scene.Tiles{
+                 transition: nil,
+                 children: [%Scene.Rescaler{id: "rescaler_1", transition: %{duration_ms: 500}}]
+               },
+               %Scene.Rescaler{id: "rescaler_2", transition: nil}
+             ]
+           } = SceneTransitions.inject(root, previous_root, @transition)
+  end
+
+  test "skips components without an id" do
+    previous_root = %Scene.View{children: [rescaler(nil, "input_0")]}
+    root = %Scene.View{children: [rescaler(nil, "input_0")]}
+
+    assert SceneTransitions.inject(root, previous_root, @transition) == root
+  end
+
+  test "keeps transitions defined in the scene" do
+    previous_root = rescaler("rescaler", "input_0")
+    root = %Scene.Rescaler{rescaler("rescaler", "input_1") | transition: %{duration_ms: 100}}
+
+    assert SceneTransitions.inject(root, previous_root, @transition) == root
+  end
+
+  test "finds ids nested in components that can't be transitioned" do
+    previous_root = %Scene.Shader{
+      shader_id: "shader",
+      resolution: %{width: 1280, height: 720},
+      children: [rescaler("rescaler", "input_0")]
+    }
+
+    root = %Scene.View{children: [rescaler("rescaler", "input_0")]}
+
+    assert %Scene.View{transition: nil, children: [%Scene.Rescaler{transition: transition}]} =
+             SceneTransitions.inject(root, previous_root, @transition)
+
+    assert transition == %{duration_ms: 500}
+  end
+
+  test "adds the easing function" do
+    easing_function = %{function_name: :cubic_bezier, points: [0.65, 0, 0.35, 1]}
+    root = rescaler("rescaler", "input_0")
+
+    assert %Scene.Rescaler{transition: transition} =
+             SceneTransitions.inject(root, root, {Membrane.Time.seconds(1), easing_function})
+
+    assert transition == %{duration_ms: 1000, easing_function: easing_function}
+  end
+
+  defp rescaler(id, input_id) do
+    %Scene.Rescaler{id: id, child: %Scene.InputStream{input_id: input_id}}
+  end
+end
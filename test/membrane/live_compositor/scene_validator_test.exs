@@ -0,0 +1,125 @@
+defmodule Membrane.LiveCompositor.SceneValidatorTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{Context, Scene, SceneValidator, State}
+
+  @context %Context{
+    video_inputs: ["input_0", "input_1"],
+    mp4_inputs: ["mp4_input"],
+    shaders: ["shader"],
+    images: ["image"],
+    web_renderers: ["web_renderer"]
+  }
+
+  test "accepts a valid scene" do
+    root = %Scene.View{
+      id: "view",
+      transition: %{duration_ms: 500},
+      children: [
+        %Scene.Rescaler{id: "rescaler", child: %Scene.InputStream{input_id: "input_0"}},
+        %Scene.Shader{
+          shader_id: "shader",
+          resolution: %{width: 1280, height: 720},
+          children: [%Scene.InputStream{input_id: "mp4_input"}]
+        },
+        %Scene.Image{image_id: "image"},
+        %Scene.WebView{instance_id: "web_renderer"},
+        %Scene.Text{text: "text", font_size: 20}
+      ]
+    }
+
+    assert SceneValidator.validate(root, state()) == :ok
+  end
+
+  test "rejects duplicate component ids" do
+    root = %Scene.View{
+      id: "duplicate",
+      children: [
+        %Scene.Tiles{
+          id: "tiles",
+          children: [%Scene.InputStream{id: "duplicate", input_id: "input_0"}]
+        },
+        %Scene.Rescaler{id: "tiles", child: %Scene.InputStream{input_id: "input_1"}}
+      ]
+    }
+
+    assert SceneValidator.validate(root, state()) ==
+             {:error,
+              [{:duplicate_component_id, "duplicate"}, {:duplicate_component_id, "tiles"}]}
+  end
+
+  test "rejects transitions of components without an id" do
+    root = %Scene.View{
+      id: "view",
+      children: [
+        %Scene.Rescaler{
+          transition: %{duration_ms: 500},
+          child: %Scene.InputStream{input_id: "input_0"}
+        },
+        %Scene.Tiles{transition: %{duration_ms: 500}}
+      ]
+    }
+
+    assert SceneValidator.validate(root, state()) ==
+             {:error,
+              [{:transition_without_id, Scene.Rescaler}, {:transition_without_id, Scene.Tiles}]}
+  end
+
+  test "rejects references to unknown inputs and renderers" do
+    root = %Scene.View{
+      children: [
+        %Scene.InputStream{input_id: "unknown_input"},
+        %Scene.Shader{shader_id: "unknown_shader", resolution: %{width: 1280, height: 720}},
+        %Scene.Image{image_id: "unknown_image"},
+        %Scene.WebView{instance_id: "unknown_web_renderer"}
+      ]
+    }
+
+    assert SceneValidator.validate(root, state()) ==
+             {:error,
+              [
+                {:unknown_input, "unknown_input"},
+                {:unknown_shader, "unknown_shader"},
+                {:unknown_image, "unknown_image"},
+                {:unknown_web_renderer, "unknown_web_renderer"}
+              ]}
+  end
+
+  test "rejects inputs that are not video inputs" do
+    context = %Context{@context | audio_inputs: ["audio_input"]}
+    root = %Scene.InputStream{input_id: "audio_input"}
+
+    assert SceneValidator.validate(root, state(context)) ==
+             {:error, [{:unknown_input, "audio_input"}]}
+  end
+
+  test "reports all errors of a single component" do
+    root = %Scene.View{
+      id: "view",
+      children: [%Scene.InputStream{id: "view", input_id: "unknown_input"}]
+    }
+
+    assert SceneValidator.validate(root, state()) ==
+             {:error, [{:duplicate_component_id, "view"}, {:unknown_input, "unknown_input"}]}
+  end
+
+  test "passes components that are not structs without validation" do
+    root = %Scene.View{
+      id: "view",
+      children: [%{type: :input_stream, id: "view", input_id: "unknown_input"}]
+    }
+
+    assert SceneValidator.validate(root, state()) == :ok
+  end
+
+  defp state(context \\ @context) do
+    %State{
+      options: nil,
+      output_framerate: {30, 1},
+      output_sample_rate: 48_000,
+      lc_address: {{127, 0, 0, 1}, 8081},
+      context: context,
+      composing_strategy: :real_time
+    }
+  end
+end
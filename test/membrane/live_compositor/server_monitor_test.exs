@@ -0,0 +1,60 @@
+defmodule Membrane.LiveCompositor.ServerMonitorTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{FakeServer, ServerMonitor}
+
+  test "notifies the bin when the server process exits" do
+    server_pid = spawn(fn -> Process.sleep(:infinity) end)
+    {:ok, monitor} = ServerMonitor.start_link({FakeServer.start_link(), server_pid, self()})
+    monitor_ref = Process.monitor(monitor)
+
+    Process.exit(server_pid, :kill)
+
+    assert_receive {:live_compositor_server_down, {:server_exited, :killed}}
+    assert_receive {:DOWN, ^monitor_ref, :process, ^monitor, :normal}
+  end
+
+  test "notifies the bin when the instance id of the server changes" do
+    {:ok, instance_ids} = Agent.start_link(fn -> ["instance_0", "instance_1"] end)
+
+    lc_address =
+      FakeServer.start_link(fn :GET, "/status", _body ->
+        instance_id = Agent.get_and_update(instance_ids, fn [id | ids] -> {id, ids ++ [id]} end)
+        {200, %{"instance_id" => instance_id}}
+      end)
+
+    {:ok, _monitor} = ServerMonitor.start_link({lc_address, nil, self()})
+
+    assert_receive {:live_compositor_server_down,
+                    {:instance_changed, "instance_0", "instance_1"}},
+                   5_000
+  end
+
+  test "notifies the bin when the server doesn't respond" do
+    lc_address = FakeServer.start_link(fn :GET, "/status", _body -> {500, %{}} end)
+    {:ok, _monitor} = ServerMonitor.start_link({lc_address, nil, self()})
+
+    assert_receive {:live_compositor_server_down, {:status_check_failed, _error}}, 5_000
+  end
+
+  test "confirms that the server is up once it responds" do
+    {:ok, responses} = Agent.start_link(fn -> [500, 200] end)
+
+    lc_address =
+      FakeServer.start_link(fn :GET, "/status", _body ->
+        status = Agent.get_and_update(responses, fn [status | rest] -> {status, rest} end)
+        {status, %{}}
+      end)
+
+    server_pid = spawn(fn -> Process.sleep(:infinity) end)
+    {:ok, monitor} = ServerMonitor.start_link({lc_address, server_pid, self()})
+
+    :ok = ServerMonitor.confirm_server_up(monitor, :tag)
+
+    assert_receive {:live_compositor_server_up, :tag}, 5_000
+    assert_received {:fake_server_request, :GET, "/status", nil}
+    assert_received {:fake_server_request, :GET, "/status", nil}
+
+    Process.exit(server_pid, :kill)
+  end
+end
@@ -0,0 +1,80 @@
+defmodule Membrane.LiveCompositor.StreamsHandlerTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{Context, FakeServer, State, StreamsHandler}
+
+  @audio_output_options %{
+    send_eos_when: nil,
+    encoder: %{type: :opus, channels: :stereo},
+    initial: %{inputs: []}
+  }
+
+  setup do
+    lc_address = FakeServer.start_link(fn _method, _path, _body -> {200, %{"port" => 9000}} end)
+
+    state = %State{
+      options: nil,
+      output_framerate: {30, 1},
+      output_sample_rate: 48_000,
+      lc_address: lc_address,
+      context: %Context{},
+      composing_strategy: :real_time
+    }
+
+    %{state: state}
+  end
+
+  test "registers UDP inputs on the port chosen by the pad options", %{state: state} do
+    options = %{transport: :udp, port: 9000, required: false, offset: nil}
+
+    assert StreamsHandler.register_audio_input_stream("input_0", options, state) == {:ok, 9000}
+
+    assert_receive {:fake_server_request, :POST, "/api/input/input_0/register",
+                    %{"transport_protocol" => "udp", "port" => 9000}}
+  end
+
+  test "registers video inputs with the decoder of their codec", %{state: state} do
+    for {codec, decoder} <- [h264: "ffmpeg_h264", vp8: "ffmpeg_vp8", vp9: "ffmpeg_vp9"] do
+      options = %{transport: :tcp, port: 9000, required: false, offset: nil, video_codec: codec}
+
+      assert {:ok, 9000} = StreamsHandler.register_video_input_stream("input_0", options, state)
+
+      assert_receive {:fake_server_request, :POST, "/api/input/input_0/register",
+                      %{"video" => %{"decoder" => ^decoder}}}
+    end
+  end
+
+  test "registers UDP outputs on the port of the socket opened by the bin", %{state: state} do
+    options = Map.merge(@audio_output_options, %{transport: :udp, port: {20_000, 20_100}})
+
+    assert {:ok, socket, port} = StreamsHandler.open_udp_socket(options.port)
+    assert port in 20_000..20_100
+
+    assert StreamsHandler.register_audio_output_stream("output_0", options, state, port) ==
+             {:ok, port}
+
+    assert_receive {:fake_server_request, :POST, "/api/output/output_0/register",
+                    %{"transport_protocol" => "udp", "ip" => "127.0.0.1", "port" => ^port}}
+
+    :ok = :gen_udp.close(socket)
+  end
+
+  test "keeps UDP ports bound until the socket is closed" do
+    assert {:ok, socket, port} = StreamsHandler.open_udp_socket({20_200, 20_300})
+    assert StreamsHandler.open_udp_socket(port) == {:error, :no_free_udp_port}
+
+    :ok = :gen_udp.close(socket)
+    assert {:ok, socket, ^port} = StreamsHandler.open_udp_socket(port)
+    :ok = :gen_udp.close(socket)
+  end
+
+  test "registers TCP outputs on the port opened by the server", %{state: state} do
+    options = Map.merge(@audio_output_options, %{transport: :tcp, port: {9000, 9100}})
+
+    assert StreamsHandler.register_audio_output_stream("output_0", options, state, nil) ==
+             {:ok, 9000}
+
+    assert_receive {:fake_server_request, :POST, "/api/output/output_0/register",
+                    %{"transport_protocol" => "tcp_server", "port" => "9000:9100"}}
+  end
+end
@@ -0,0 +1,115 @@
+defmodule Membrane.LiveCompositor.TimelineTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.LiveCompositor.{Request, Scene, Timeline}
+
+  describe "add_cue/4" do
+    test "adds cues in order" do
+      timeline =
+        Timeline.new("timeline", start: :now)
+        |> Timeline.show_scene("scene", 0, "output_0", %Scene.View{})
+        |> Timeline.drop_input("drop_input", Membrane.Time.seconds(1), "input_0")
+        |> Timeline.drop_output("drop_output", Membrane.Time.seconds(2), "output_0")
+
+      assert timeline.start == :now
+
+      assert [
+               %Timeline.Cue{id: "scene", at: 0, request: %Request.UpdateVideoOutput{}},
+               %Timeline.Cue{id: "drop_input", request: %Request.UnregisterInput{}},
+               %Timeline.Cue{id: "drop_output", request: %Request.UnregisterOutput{}}
+             ] = timeline.cues
+    end
+
+    test "rejects duplicate cue ids" do
+      timeline = Timeline.drop_input(Timeline.new("timeline"), "cue", 0, "input_0")
+
+      assert_raise ArgumentError, fn ->
+        Timeline.drop_input(timeline, "cue", Membrane.Time.seconds(1), "input_1")
+      end
+    end
+
+    test "rejects requests that can't be applied by a cue" do
+      request = %Request.RegisterShader{shader_id: "shader", source: "source"}
+
+      assert_raise ArgumentError, fn ->
+        Timeline.add_cue(Timeline.new("timeline"), "cue", 0, request)
+      end
+
+      refute Timeline.cue_request?(request)
+      refute Timeline.cue_request?(:not_a_request)
+      assert Timeline.cue_request?(%Request.UnregisterInput{input_id: "input_0"})
+    end
+  end
+
+  describe "fade_audio/5" do
+    test "interpolates volumes and removes muted inputs at the end" do
+      timeline =
+        Timeline.new("timeline")
+        |> Timeline.fade_audio("fade", Membrane.Time.seconds(1), "output_0",
+          from: [%{input_id: "music", volume: 1.0}],
+          to: [%{input_id: "music", volume: 0.0}],
+          duration: Membrane.Time.seconds(2),
+          steps: 4
+        )
+
+      assert Enum.map(timeline.cues, &{&1.id, &1.at, &1.request.inputs}) == [
+               {{:fade, "fade", 1}, Membrane.Time.milliseconds(1500),
+                [%{input_id: "music", volume: 0.75}]},
+               {{:fade, "fade", 2}, Membrane.Time.seconds(2),
+                [%{input_id: "music", volume: 0.5}]},
+               {{:fade, "fade", 3}, Membrane.Time.milliseconds(2500),
+                [%{input_id: "music", volume: 0.25}]},
+               {{:fade, "fade", 4}, Membrane.Time.seconds(3), []}
+             ]
+
+      assert Enum.all?(timeline.cues, &(&1.request.output_id == "output_0"))
+    end
+
+    test "treats missing inputs as muted and missing volumes as 1.0" do
+      timeline =
+        Timeline.new("timeline")
+        |> Timeline.fade_audio("fade", 0, "output_0",
+          from: [%{input_id: "input_0", volume: nil}],
+          to: [%{input_id: "input_1"}],
+          duration: Membrane.Time.seconds(1),
+          steps: 2
+        )
+
+      assert Enum.map(timeline.cues, & &1.request.inputs) == [
+               [%{input_id: "input_0", volume: 0.5}, %{input_id: "input_1", volume: 0.5}],
+               [%{input_id: "input_1", volume: 1.0}]
+             ]
+    end
+
+    test "uses 10 steps by default" do
+      timeline =
+        Timeline.new("timeline")
+        |> Timeline.fade_audio("fade", 0, "output_0",
+          from: [],
+          to: [%{input_id: "input_0", volume: 1.0}],
+          duration: Membrane.Time.seconds(1)
+        )
+
+      assert length(timeline.cues) == 10
+      assert List.last(timeline.cues).at == Membrane.Time.seconds(1)
+    end
+  end
+
+  describe "cue_matches?/2" do
+    test "matches cue ids" do
+      assert Timeline.cue_matches?("cue", ["cue"])
+      refute Timeline.cue_matches?("cue", ["other_cue"])
+    end
+
+    test "matches fade steps by the id of the fade" do
+      assert Timeline.cue_matches?({:fade, "fade", 3}, ["fade"])
+      assert Timeline.cue_matches?({:fade, "fade", 3}, [{:fade, "fade", 3}])
+      refute Timeline.cue_matches?({:fade, "fade", 3}, [{:fade, "fade", 4}])
+    end
+
+    test "doesn't treat user cue ids as fade steps" do
+      refute Timeline.cue_matches?({:x, 1}, [:x])
+      assert Timeline.cue_matches?({:x, 1}, [{:x, 1}])
+    end
+  end
+end
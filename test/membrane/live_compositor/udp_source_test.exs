@@ -0,0 +1,31 @@
+defmodule Membrane.LiveCompositor.UdpSourceTest do
+  use ExUnit.Case, async: true
+
+  alias Membrane.{Buffer, RemoteStream}
+  alias Membrane.LiveCompositor.UdpSource
+
+  test "receives packets sent before it took over the socket" do
+    {:ok, socket} = :gen_udp.open(0, [:binary, active: false])
+    {:ok, port} = :inet.port(socket)
+    {[], state} = UdpSource.handle_init(%{}, %UdpSource{socket: socket})
+
+    {:ok, sender} = :gen_udp.open(0, [:binary])
+    :ok = :gen_udp.send(sender, {127, 0, 0, 1}, port, "packet")
+
+    assert {[
+              stream_format: {:output, %RemoteStream{type: :packetized}},
+              notify_parent: {:udp_socket_owner, source_pid}
+            ], state} = UdpSource.handle_playing(%{}, state)
+
+    :ok = :gen_udp.controlling_process(socket, source_pid)
+    assert {[], state} = UdpSource.handle_parent_notification(:socket_ready, %{}, state)
+
+    assert_receive {:udp, ^socket, _address, _port, "packet"} = message
+
+    assert {[buffer: {:output, %Buffer{payload: "packet"}}], _state} =
+             UdpSource.handle_info(message, %{}, state)
+
+    :gen_udp.close(sender)
+    :gen_udp.close(socket)
+  end
+end
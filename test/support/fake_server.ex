@@ -0,0 +1,81 @@
+defmodule Membrane.LiveCompositor.FakeServer do
+  @moduledoc false
+  # Minimal HTTP server that stands in for the LiveCompositor server in tests.
+  #
+  # Every request is sent to the process that started the server as
+  # `{:fake_server_request, method, path, body}` and answered with the JSON body
+  # returned by the `respond` function. Accepted connections are reported as
+  # `:fake_server_connection`.
+
+  @type respond_fun ::
+          (method :: atom(), path :: String.t(), body :: any() ->
+             {status :: non_neg_integer(), body :: any()})
+
+  @spec start_link(respond_fun()) :: {:inet.ip_address(), :inet.port_number()}
+  def start_link(respond \\ fn _method, _path, _body -> {200, %{}} end) do
+    test_process = self()
+
+    {:ok, listen_socket} =
+      :gen_tcp.listen(0, [:binary, ip: {127, 0, 0, 1}, packet: :http_bin, active: false])
+
+    {:ok, port} = :inet.port(listen_socket)
+    spawn_link(fn -> accept_loop(listen_socket, test_process, respond) end)
+
+    {{127, 0, 0, 1}, port}
+  end
+
+  defp accept_loop(listen_socket, test_process, respond) do
+    {:ok, socket} = :gen_tcp.accept(listen_socket)
+    send(test_process, :fake_server_connection)
+    pid = spawn_link(fn -> serve(socket, test_process, respond) end)
+    :ok = :gen_tcp.controlling_process(socket, pid)
+    accept_loop(listen_socket, test_process, respond)
+  end
+
+  defp serve(socket, test_process, respond) do
+    with {:ok, {:http_request, method, {:abs_path, path}, _version}} <- :gen_tcp.recv(socket, 0),
+         {:ok, content_length} <- read_headers(socket, 0),
+         {:ok, body} <- read_body(socket, content_length) do
+      send(test_process, {:fake_server_request, method, path, body})
+      {status, response_body} = respond.(method, path, body)
+      response_body = Jason.encode!(response_body)
+
+      :ok =
+        :gen_tcp.send(socket, [
+          "HTTP/1.1 #{status} Status\r\n",
+          "Content-Type: application/json\r\n",
+          "Content-Length: #{byte_size(response_body)}\r\n\r\n",
+          response_body
+        ])
+
+      serve(socket, test_process, respond)
+    else
+      _closed -> :gen_tcp.close(socket)
+    end
+  end
+
+  defp read_headers(socket, content_length) do
+    case :gen_tcp.recv(socket, 0) do
+      {:ok, :http_eoh} ->
+        {:ok, content_length}
+
+      {:ok, {:http_header, _index, :"Content-Length", _reserved, value}} ->
+        read_headers(socket, String.to_integer(value))
+
+      {:ok, {:http_header, _index, _field, _reserved, _value}} ->
+        read_headers(socket, content_length)
+
+      error ->
+        error
+    end
+  end
+
+  defp read_body(_socket, 0), do: {:ok, nil}
+
+  defp read_body(socket, content_length) do
+    :ok = :inet.setopts(socket, packet: :raw)
+    {:ok, body} = :gen_tcp.recv(socket, content_length)
+    :ok = :inet.setopts(socket, packet: :http_bin)
+    {:ok, Jason.decode!(body)}
+  end
+end